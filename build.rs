@@ -1,8 +1,32 @@
-const COMMANDS: &[&str] = &["read_config", "write_config", "set_darkmode", "get_schemes", "get_scheme_by_id"];
+const COMMANDS: &[&str] = &[
+    "read_config",
+    "write_config",
+    "set_darkmode",
+    "get_schemes",
+    "get_scheme_by_id",
+    "subscribe_config",
+    "unsubscribe_config",
+    "get_scheme_diagnostics",
+    "install_scheme",
+    "duplicate_scheme",
+    "update_scheme",
+    "delete_scheme",
+    "get_raw_scheme",
+    "check_scheme_contrast",
+    "generate_scheme",
+    "scheme_from_wallpaper",
+    "export_scheme",
+    "apply_terminal_theme",
+    "apply_gtk_theme",
+    "apply_qt_theme",
+    "apply_x11_theme",
+    "export_tokens",
+    "get_theme_css",
+];
 
 fn main() {
-  tauri_plugin::Builder::new(COMMANDS)
-    .android_path("android")
-    .ios_path("ios")
-    .build();
+    tauri_plugin::Builder::new(COMMANDS)
+        .android_path("android")
+        .ios_path("ios")
+        .build();
 }
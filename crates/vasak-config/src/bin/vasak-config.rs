@@ -0,0 +1,395 @@
+//! Command-line access to the Vasak configuration.
+//!
+//! Uses the same [`ConfigStore`] as the Tauri plugin, so paths, atomic writes
+//! and the gsettings sync behave exactly like in the desktop apps.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use vasak_config::{
+    AppliedTheme, Color, ConfigStore, ContrastOptions, ExportFormat, GenerateOptions,
+    SchemeVariant, TokenFormat, VSKConfig,
+};
+
+const USAGE: &str = "Usage: vasak-config <command> [args]
+
+Commands:
+  get <pointer>            Print the value at a JSON pointer (e.g. /style/radius)
+  set <pointer> <value>    Set a value; <value> is parsed as JSON, or used as a string
+  darkmode on|off|toggle   Change dark mode and sync the system theme
+  schemes list             List the available color schemes
+  schemes show <id>        Print a color scheme as JSON
+  schemes install <file>   Install a scheme file (JSON, base16/base24 YAML or .itermcolors)
+                           into the user scheme directory
+  schemes duplicate <id> <new-id>
+                           Copy a scheme as a new user scheme
+  schemes delete <id>      Delete a user scheme
+  schemes generate <accent> [id]
+                           Print a scheme generated from an accent color (e.g. '#3584e4')
+  schemes wallpaper [file] [--save]
+                           Print a scheme derived from an image (default: the wallpaper);
+                           --save stores it as the vasak-dynamic scheme (needs the wallpaper feature)
+  schemes export <id> <format> [dark|light]
+                           Print a scheme as alacritty, kitty, foot, wezterm, gnome-terminal,
+                           gtk3, gtk4, kde, qt5ct, qt6ct, vscode, neovim, base16, base24,
+                           itermcolors, xresources or xsettingsd
+  schemes contrast <id> [--apca]
+                           Check the WCAG contrast of a scheme; fails if a pair is below AA
+  terminal apply [format...]
+                           Write the active scheme for terminal emulators (default: those in
+                           PATH) and keep it in sync; each emulator's config must include the
+                           written file once (see the README)
+  gtk apply                Write the active scheme into the managed block of gtk-3.0/gtk.css
+                           and gtk-4.0/gtk.css and keep it in sync
+  qt apply                 Write the active scheme for KDE, qt5ct and qt6ct, select it and
+                           keep it in sync
+  x11 apply                Write the active scheme into ~/.Xresources and the theme, icons and
+                           fonts into xsettingsd.conf, and keep them in sync
+  tokens export json|css|scss
+                           Print the active scheme, corner radius and fonts as design tokens
+  theme-css                Print the stylesheet with the theme variables for webviews
+  validate [file]          Check that a file (default: the config file) is a valid config
+  path                     Print the config file path
+  apply                    Re-apply the system theme and icons from the current config
+  serve                    Publish org.vasak.Config on the session bus (needs the dbus feature)";
+
+enum CliError {
+    Usage(String),
+    Failed(String),
+    /// Quien leía la salida cerró la tubería (p. ej. `| head`); se sale sin ruido.
+    BrokenPipe,
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::BrokenPipe {
+            CliError::BrokenPipe
+        } else {
+            CliError::Failed(e.to_string())
+        }
+    }
+}
+
+/// Como `print!`, pero devuelve el error en vez de entrar en pánico si stdout
+/// está cerrado.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        write!(std::io::stdout().lock(), $($arg)*).map_err(CliError::from)
+    };
+}
+
+/// Como `println!`, con el mismo manejo de errores que `out!`.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        writeln!(std::io::stdout().lock(), $($arg)*).map_err(CliError::from)
+    };
+}
+
+impl From<vasak_config::Error> for CliError {
+    fn from(e: vasak_config::Error) -> Self {
+        CliError::Failed(e.to_string())
+    }
+}
+
+fn usage<T>(message: &str) -> Result<T, CliError> {
+    Err(CliError::Usage(message.to_string()))
+}
+
+fn print_value(value: &serde_json::Value) -> Result<(), CliError> {
+    // Los strings se imprimen sin comillas para facilitar su uso en scripts.
+    match value {
+        serde_json::Value::String(text) => outln!("{}", text),
+        other => outln!(
+            "{}",
+            serde_json::to_string_pretty(other).map_err(vasak_config::Error::Json)?
+        ),
+    }
+}
+
+/// Una línea por formato aplicado; falla si alguno no se pudo aplicar.
+fn report_applied(applied: Vec<AppliedTheme>) -> Result<(), CliError> {
+    let mut failed = false;
+    for theme in applied {
+        match theme.error {
+            Some(error) => {
+                failed = true;
+                eprintln!("{}: {}", theme.format.as_str(), error);
+            }
+            None => outln!("{}\t{}", theme.format.as_str(), theme.target)?,
+        }
+    }
+    if failed {
+        Err(CliError::Failed(
+            "some themes could not be applied".to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Sin `follow_theme_changes` en este proceso, los temas aplicados se
+/// sincronizan a mano; solo se informan los formatos que fallan.
+async fn sync_applied_themes(store: &ConfigStore) -> Result<(), CliError> {
+    for theme in store.sync_applied_themes().await? {
+        if let Some(error) = theme.error {
+            eprintln!("vasak-config: {}: {}", theme.format.as_str(), error);
+        }
+    }
+    Ok(())
+}
+
+async fn run(args: &[String]) -> Result<(), CliError> {
+    let store = ConfigStore::new();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["get", pointer] => match store.get_value(pointer).await? {
+            Some(value) => print_value(&value),
+            None => Err(CliError::Failed(format!("No value at {}", pointer))),
+        },
+        ["set", pointer, raw] => {
+            let value = serde_json::from_str(raw)
+                .unwrap_or_else(|_| serde_json::Value::String(raw.to_string()));
+            let before = store.load_config().await?;
+            let config = store.set_value(pointer, value).await?;
+            if ConfigStore::applied_themes_changed(&before, &config) {
+                sync_applied_themes(&store).await?;
+            }
+            Ok(())
+        }
+        ["darkmode", mode] => {
+            let darkmode = match *mode {
+                "on" => true,
+                "off" => false,
+                "toggle" => !store.load_config().await?.style.darkmode,
+                _ => return usage("darkmode expects on, off or toggle"),
+            };
+            store.set_darkmode(darkmode).await?;
+            sync_applied_themes(&store).await
+        }
+        ["schemes", "list"] => {
+            for scheme in store.load_schemes().await? {
+                outln!(
+                    "{}\t{}\t{}",
+                    scheme.scheme.id,
+                    scheme.scheme.name,
+                    scheme.path
+                )?;
+            }
+            Ok(())
+        }
+        ["schemes", "show", id] => match store.get_scheme_by_id(id).await? {
+            Some(scheme) => {
+                print_value(&serde_json::to_value(&scheme).map_err(vasak_config::Error::Json)?)
+            }
+            None => Err(CliError::Failed(format!("Scheme not found: {}", id))),
+        },
+        ["schemes", "install", file] => {
+            let scheme = store.install_scheme(file).await?;
+            outln!("{}", scheme.path)?;
+            Ok(())
+        }
+        ["schemes", "duplicate", id, new_id] => {
+            let scheme = store.duplicate_scheme(id, new_id).await?;
+            outln!("{}", scheme.path)?;
+            Ok(())
+        }
+        ["schemes", "delete", id] => {
+            store.delete_scheme(id).await?;
+            Ok(())
+        }
+        ["schemes", "generate", accent] | ["schemes", "generate", accent, _] => {
+            let accent = Color::parse(accent).map_err(|e| CliError::Failed(e.to_string()))?;
+            let options = GenerateOptions {
+                id: args.get(3).map(|id| id.to_string()),
+                ..GenerateOptions::default()
+            };
+            let scheme = vasak_config::generate_scheme(&accent, &options);
+            print_value(&serde_json::to_value(&scheme).map_err(vasak_config::Error::Json)?)
+        }
+        #[cfg(feature = "wallpaper")]
+        ["schemes", "wallpaper", rest @ ..] => {
+            let save = rest.contains(&"--save");
+            let file = match rest {
+                [] | ["--save"] => None,
+                [file] | [file, "--save"] | ["--save", file] => Some(*file),
+                _ => return usage("schemes wallpaper expects [file] [--save]"),
+            };
+            let options = vasak_config::WallpaperSchemeOptions { save };
+            let scheme = store.scheme_from_wallpaper(file, options).await?;
+            print_value(&serde_json::to_value(&scheme).map_err(vasak_config::Error::Json)?)
+        }
+        ["schemes", "export", id, format] | ["schemes", "export", id, format, _] => {
+            let variant = match args.get(4).copied() {
+                None => None,
+                Some("dark") => Some(SchemeVariant::Dark),
+                Some("light") => Some(SchemeVariant::Light),
+                Some(_) => return usage("the variant must be dark or light"),
+            };
+            let format: ExportFormat = format.parse()?;
+            out!("{}", store.export_scheme(id, variant, format).await?)?;
+            Ok(())
+        }
+        ["schemes", "contrast", id] | ["schemes", "contrast", id, "--apca"] => {
+            let options = ContrastOptions {
+                apca: args.len() == 4,
+            };
+            let report = store.check_scheme_contrast(id, options).await?;
+            for pair in &report.pairs {
+                let verdict = if pair.wcag.aaa {
+                    "AAA"
+                } else if pair.wcag.aa {
+                    "AA"
+                } else if pair.wcag.aa_large {
+                    "AA large"
+                } else {
+                    "fail"
+                };
+                let apca = pair
+                    .apca
+                    .map(|lc| format!("\tLc {:.1}", lc))
+                    .unwrap_or_default();
+                outln!(
+                    "{}\t{} on {}\t{:.2}:1\t{}{}",
+                    pair.variant.as_str(),
+                    pair.foreground_role,
+                    pair.background_role,
+                    pair.ratio,
+                    verdict,
+                    apca
+                )?;
+            }
+            if report.passes_aa {
+                Ok(())
+            } else {
+                Err(CliError::Failed(format!(
+                    "Scheme {} has pairs below WCAG AA",
+                    report.scheme_id
+                )))
+            }
+        }
+        ["terminal", "apply", formats @ ..] => {
+            let formats = if formats.is_empty() {
+                None
+            } else {
+                Some(
+                    formats
+                        .iter()
+                        .map(|format| format.parse())
+                        .collect::<Result<Vec<ExportFormat>, _>>()?,
+                )
+            };
+            report_applied(store.apply_terminal_theme(formats).await?)
+        }
+        ["gtk", "apply"] => report_applied(store.apply_gtk_theme().await?),
+        ["qt", "apply"] => report_applied(store.apply_qt_theme().await?),
+        ["x11", "apply"] => report_applied(store.apply_x11_theme().await?),
+        ["tokens", "export", format] => {
+            let format: TokenFormat = format.parse()?;
+            out!("{}", store.export_tokens(format).await?)?;
+            Ok(())
+        }
+        ["theme-css"] => {
+            out!("{}", store.theme_css().await?)?;
+            Ok(())
+        }
+        ["validate"] | ["validate", _] => {
+            let path = match args.get(1) {
+                Some(file) => std::path::PathBuf::from(file),
+                None => store.config_path()?,
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                CliError::Failed(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            serde_json::from_str::<VSKConfig>(&content)
+                .map_err(|e| CliError::Failed(format!("{}: {}", path.display(), e)))?;
+            outln!("{}: ok", path.display())?;
+            Ok(())
+        }
+        ["path"] => {
+            outln!("{}", store.config_path()?.display())?;
+            Ok(())
+        }
+        ["apply"] => {
+            store.apply_system_theme().await?;
+            Ok(())
+        }
+        #[cfg(feature = "dbus")]
+        ["serve"] => serve(store).await,
+        [] => usage("missing command"),
+        _ => usage(&format!("unknown command: {}", args.join(" "))),
+    }
+}
+
+/// Hosts the D-Bus service and keeps the cache in sync with external edits.
+#[cfg(feature = "dbus")]
+async fn serve(store: ConfigStore) -> Result<(), CliError> {
+    let _service = vasak_config::dbus::DbusService::start(store.clone()).await?;
+
+    let runtime = tokio::runtime::Handle::current();
+    let watched_store = store.clone();
+    let _watcher = store.watch(move || {
+        let store = watched_store.clone();
+        runtime.spawn(async move {
+            if let Err(e) = store.reload_from_file().await {
+                eprintln!("vasak-config: failed to reload config: {}", e);
+            }
+        });
+    })?;
+    let runtime = tokio::runtime::Handle::current();
+    let synced_store = store.clone();
+    let _scheme_watcher = store.watch_schemes(move |_| {
+        let store = synced_store.clone();
+        runtime.spawn(async move {
+            if let Err(e) = store.sync_applied_themes().await {
+                eprintln!("vasak-config: failed to sync applied themes: {}", e);
+            }
+        });
+    })?;
+
+    let themed_store = store.clone();
+    tokio::spawn(async move { themed_store.follow_theme_changes().await });
+    #[cfg(feature = "wallpaper")]
+    {
+        let wallpaper_store = store.clone();
+        tokio::spawn(async move { wallpaper_store.follow_wallpaper().await });
+    }
+    std::future::pending::<()>().await;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if matches!(
+        args.first().map(String::as_str),
+        Some("-h" | "--help" | "help")
+    ) {
+        let _ = writeln!(std::io::stdout().lock(), "{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("vasak-config: cannot start runtime: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match runtime.block_on(run(&args)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::BrokenPipe) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("vasak-config: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("vasak-config: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,521 @@
+//! CSS color values used by the schemes.
+//!
+//! A [`Color`] keeps the text it was written with, so schemes round-trip
+//! unchanged, plus its sRGB value for conversions. [`Color::format`] renders
+//! it in a normalized notation chosen by the caller.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A color that failed to parse.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid color {input:?}: {reason}")]
+pub struct ColorParseError {
+    pub input: String,
+    pub reason: String,
+}
+
+/// Notation used by [`Color::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFormat {
+    /// `#rrggbb`, or `#rrggbbaa` when the color is translucent.
+    #[default]
+    Hex,
+    /// `rgb(r, g, b)`, or `rgba(r, g, b, a)` when the color is translucent.
+    Rgb,
+    /// `oklch(l% c h)`, with ` / a` when the color is translucent.
+    Oklch,
+}
+
+/// A parsed CSS color.
+///
+/// Accepted notations: `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb()`/`rgba()`,
+/// `hsl()`/`hsla()` and `oklch()`, both with commas and with the
+/// space-separated syntax. Named colors and everything else are rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Color {
+    text: String,
+    /// sRGB sin recortar al gamut: `oklch()` puede quedar fuera de rango.
+    red: f64,
+    green: f64,
+    blue: f64,
+    alpha: f64,
+}
+
+impl Color {
+    /// Builds a color from sRGB channels and alpha in `0.0..=1.0`; its text
+    /// is the hex notation.
+    pub fn from_rgba(red: f64, green: f64, blue: f64, alpha: f64) -> Self {
+        let mut color = Self {
+            text: String::new(),
+            red,
+            green,
+            blue,
+            alpha: alpha.clamp(0.0, 1.0),
+        };
+        color.text = color.format(ColorFormat::Hex);
+        color
+    }
+
+    /// Builds a color from OKLCH: lightness in `0.0..=1.0`, chroma and hue in
+    /// degrees. Its text is the `oklch()` notation.
+    pub fn from_oklch(lightness: f64, chroma: f64, hue: f64, alpha: f64) -> Self {
+        let (red, green, blue) = oklch_to_srgb(lightness, chroma, hue);
+        let mut color = Self {
+            text: String::new(),
+            red,
+            green,
+            blue,
+            alpha: alpha.clamp(0.0, 1.0),
+        };
+        color.text = color.format(ColorFormat::Oklch);
+        color
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ColorParseError> {
+        let text = input.trim();
+        let error = |reason: &str| ColorParseError {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let (red, green, blue, alpha) = if let Some(hex) = text.strip_prefix('#') {
+            parse_hex(hex).ok_or_else(|| error("expected #rgb, #rrggbb or #rrggbbaa"))?
+        } else {
+            let (name, args) = split_function(text)
+                .ok_or_else(|| error("expected a hex color or rgb(), hsl() or oklch()"))?;
+            match name.as_str() {
+                "rgb" | "rgba" => parse_rgb(&args).map_err(|reason| error(&reason))?,
+                "hsl" | "hsla" => parse_hsl(&args).map_err(|reason| error(&reason))?,
+                "oklch" => parse_oklch(&args).map_err(|reason| error(&reason))?,
+                _ => return Err(error(&format!("unsupported color function {}()", name))),
+            }
+        };
+
+        Ok(Self {
+            text: text.to_string(),
+            red,
+            green,
+            blue,
+            alpha,
+        })
+    }
+
+    /// The text the color was written with.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// sRGB channels and alpha in `0.0..=1.0`, clipped to the sRGB gamut.
+    pub fn to_rgba(&self) -> (f64, f64, f64, f64) {
+        (
+            self.red.clamp(0.0, 1.0),
+            self.green.clamp(0.0, 1.0),
+            self.blue.clamp(0.0, 1.0),
+            self.alpha,
+        )
+    }
+
+    /// 8-bit sRGB channels and alpha.
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let (red, green, blue, alpha) = self.to_rgba();
+        [red, green, blue, alpha].map(|channel| (channel * 255.0).round() as u8)
+    }
+
+    /// OKLCH lightness (`0.0..=1.0`), chroma and hue in degrees.
+    pub fn to_oklch(&self) -> (f64, f64, f64) {
+        srgb_to_oklch(self.red, self.green, self.blue)
+    }
+
+    /// Whether the color fits in sRGB without clipping.
+    pub fn in_srgb_gamut(&self) -> bool {
+        const EPSILON: f64 = 1e-6;
+        [self.red, self.green, self.blue]
+            .iter()
+            .all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Same color with another alpha.
+    pub fn with_alpha(&self, alpha: f64) -> Self {
+        Self::from_rgba(self.red, self.green, self.blue, alpha)
+    }
+
+    /// Renders the color in `format`.
+    pub fn format(&self, format: ColorFormat) -> String {
+        let [red, green, blue, alpha8] = self.to_rgba8();
+        let translucent = self.alpha < 1.0;
+        match format {
+            ColorFormat::Hex if translucent => {
+                format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha8)
+            }
+            ColorFormat::Hex => format!("#{:02x}{:02x}{:02x}", red, green, blue),
+            ColorFormat::Rgb if translucent => format!(
+                "rgba({}, {}, {}, {})",
+                red,
+                green,
+                blue,
+                format_number(self.alpha, 3)
+            ),
+            ColorFormat::Rgb => format!("rgb({}, {}, {})", red, green, blue),
+            ColorFormat::Oklch => {
+                let (lightness, chroma, hue) = self.to_oklch();
+                let body = format!(
+                    "{}% {} {}",
+                    format_number(lightness * 100.0, 2),
+                    format_number(chroma, 4),
+                    format_number(hue, 2)
+                );
+                if translucent {
+                    format!("oklch({} / {})", body, format_number(self.alpha, 3))
+                } else {
+                    format!("oklch({})", body)
+                }
+            }
+        }
+    }
+
+    /// Same color whose text is rewritten in `format`.
+    pub fn normalized(&self, format: ColorFormat) -> Self {
+        Self {
+            text: self.format(format),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Número con `decimals` decimales como máximo y sin ceros de sobra.
+fn format_number(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        text.as_str()
+    };
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<(f64, f64, f64, f64)> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).ok();
+    let byte = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    let channel = |value: u8| f64::from(value) / 255.0;
+
+    match hex.len() {
+        3 => Some((
+            channel(digit(0)? * 17),
+            channel(digit(1)? * 17),
+            channel(digit(2)? * 17),
+            1.0,
+        )),
+        6 => Some((channel(byte(0)?), channel(byte(2)?), channel(byte(4)?), 1.0)),
+        8 => Some((
+            channel(byte(0)?),
+            channel(byte(2)?),
+            channel(byte(4)?),
+            channel(byte(6)?),
+        )),
+        _ => None,
+    }
+}
+
+/// Separa `name(args)` y devuelve el nombre en minúsculas y los argumentos.
+fn split_function(text: &str) -> Option<(String, Vec<String>)> {
+    let open = text.find('(')?;
+    let inner = text[open + 1..].strip_suffix(')')?;
+    let name = text[..open].trim().to_ascii_lowercase();
+
+    // Sintaxis clásica con comas, o la moderna separada por espacios con
+    // el alfa tras una barra.
+    let args: Vec<String> = if inner.contains(',') {
+        inner.split(',').map(|arg| arg.trim().to_string()).collect()
+    } else {
+        let (channels, alpha) = match inner.split_once('/') {
+            Some((channels, alpha)) => (channels, Some(alpha.trim())),
+            None => (inner, None),
+        };
+        channels
+            .split_whitespace()
+            .map(str::to_string)
+            .chain(alpha.map(str::to_string))
+            .collect()
+    };
+
+    if args.iter().any(String::is_empty) {
+        return None;
+    }
+    Some((name, args))
+}
+
+fn parse_number(arg: &str) -> Result<f64, String> {
+    arg.parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| format!("invalid number {:?}", arg))
+}
+
+/// Acepta un número o un porcentaje; `percent_scale` es el valor de `100%`.
+fn parse_number_or_percent(arg: &str, percent_scale: f64) -> Result<f64, String> {
+    match arg.strip_suffix('%') {
+        Some(percent) => Ok(parse_number(percent)? / 100.0 * percent_scale),
+        None => parse_number(arg),
+    }
+}
+
+fn parse_percent(arg: &str) -> Result<f64, String> {
+    arg.strip_suffix('%')
+        .ok_or_else(|| format!("expected a percentage, got {:?}", arg))
+        .and_then(parse_number)
+        .map(|value| value / 100.0)
+}
+
+fn parse_hue(arg: &str) -> Result<f64, String> {
+    let degrees = parse_number(arg.strip_suffix("deg").unwrap_or(arg))?;
+    Ok(degrees.rem_euclid(360.0))
+}
+
+fn parse_alpha(args: &[String], channels: usize) -> Result<f64, String> {
+    match args.len() {
+        n if n == channels => Ok(1.0),
+        n if n == channels + 1 => {
+            Ok(parse_number_or_percent(&args[channels], 1.0)?.clamp(0.0, 1.0))
+        }
+        n => Err(format!(
+            "expected {} or {} arguments, got {}",
+            channels,
+            channels + 1,
+            n
+        )),
+    }
+}
+
+fn parse_rgb(args: &[String]) -> Result<(f64, f64, f64, f64), String> {
+    let alpha = parse_alpha(args, 3)?;
+    let channel = |arg: &String| -> Result<f64, String> {
+        Ok((parse_number_or_percent(arg, 255.0)? / 255.0).clamp(0.0, 1.0))
+    };
+    Ok((
+        channel(&args[0])?,
+        channel(&args[1])?,
+        channel(&args[2])?,
+        alpha,
+    ))
+}
+
+fn parse_hsl(args: &[String]) -> Result<(f64, f64, f64, f64), String> {
+    let alpha = parse_alpha(args, 3)?;
+    let hue = parse_hue(&args[0])?;
+    let saturation = parse_percent(&args[1])?.clamp(0.0, 1.0);
+    let lightness = parse_percent(&args[2])?.clamp(0.0, 1.0);
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let offset = lightness - chroma / 2.0;
+    Ok((red + offset, green + offset, blue + offset, alpha))
+}
+
+fn parse_oklch(args: &[String]) -> Result<(f64, f64, f64, f64), String> {
+    let alpha = parse_alpha(args, 3)?;
+    let lightness = parse_number_or_percent(&args[0], 1.0)?.clamp(0.0, 1.0);
+    // En CSS, `100%` de croma equivale a 0.4.
+    let chroma = parse_number_or_percent(&args[1], 0.4)?.max(0.0);
+    let hue = parse_hue(&args[2])?;
+    let (red, green, blue) = oklch_to_srgb(lightness, chroma, hue);
+    Ok((red, green, blue, alpha))
+}
+
+fn srgb_to_linear(channel: f64) -> f64 {
+    if channel.abs() <= 0.04045 {
+        channel / 12.92
+    } else {
+        channel.signum() * ((channel.abs() + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f64) -> f64 {
+    if channel.abs() <= 0.0031308 {
+        channel * 12.92
+    } else {
+        channel.signum() * (1.055 * channel.abs().powf(1.0 / 2.4) - 0.055)
+    }
+}
+
+/// Conversión de https://bottosson.github.io/posts/oklab/
+fn srgb_to_oklch(red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+    let (r, g, b) = (
+        srgb_to_linear(red),
+        srgb_to_linear(green),
+        srgb_to_linear(blue),
+    );
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    let lightness = 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s;
+    let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+    let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+
+    let chroma = (a * a + b * b).sqrt();
+    // Los grises no tienen tono definido.
+    let hue = if chroma < 1e-4 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (lightness, chroma, hue)
+}
+
+fn oklch_to_srgb(lightness: f64, chroma: f64, hue: f64) -> (f64, f64, f64) {
+    let (sin, cos) = hue.to_radians().sin_cos();
+    let (a, b) = (chroma * cos, chroma * sin);
+
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    let r = 4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s;
+    let g = -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s;
+    let b = -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s;
+
+    (linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba8(input: &str) -> [u8; 4] {
+        Color::parse(input).unwrap().to_rgba8()
+    }
+
+    #[test]
+    fn parse_keeps_the_original_text() {
+        let color = Color::parse("  RGB(53, 132, 228) ").unwrap();
+        assert_eq!(color.as_str(), "RGB(53, 132, 228)");
+        assert_eq!(
+            serde_json::to_string(&color).unwrap(),
+            "\"RGB(53, 132, 228)\""
+        );
+    }
+
+    #[test]
+    fn hex_notations() {
+        assert_eq!(rgba8("#3584e4"), [0x35, 0x84, 0xe4, 255]);
+        assert_eq!(rgba8("#fA0"), [0xff, 0xaa, 0x00, 255]);
+        assert_eq!(rgba8("#3584e480"), [0x35, 0x84, 0xe4, 0x80]);
+        assert!(Color::parse("#3584e").is_err());
+        assert!(Color::parse("#zzzzzz").is_err());
+        assert!(Color::parse("blue").is_err());
+    }
+
+    #[test]
+    fn rgb_hsl_and_oklch_notations() {
+        assert_eq!(rgba8("rgb(53, 132, 228)"), [53, 132, 228, 255]);
+        assert_eq!(rgba8("rgb(53 132 228 / 50%)"), [53, 132, 228, 128]);
+        assert_eq!(rgba8("rgba(100%, 0%, 0%, 0.5)"), [255, 0, 0, 128]);
+        assert_eq!(rgba8("hsl(0, 100%, 50%)"), [255, 0, 0, 255]);
+        assert_eq!(rgba8("hsl(120deg 100% 25%)"), [0, 128, 0, 255]);
+        assert_eq!(rgba8("hsla(240, 100%, 50%, 0.25)"), [0, 0, 255, 64]);
+        assert_eq!(rgba8("oklch(100% 0 0)"), [255, 255, 255, 255]);
+        assert_eq!(rgba8("oklch(0 0 0)"), [0, 0, 0, 255]);
+        assert!(Color::parse("rgb(1, 2)").is_err());
+        assert!(Color::parse("lab(50% 10 10)").is_err());
+    }
+
+    #[test]
+    fn format_round_trips_through_every_notation() {
+        for input in ["#3584e4", "#e01b24", "#ffffff", "#000000", "#3584e480"] {
+            let color = Color::parse(input).unwrap();
+            for format in [ColorFormat::Hex, ColorFormat::Rgb, ColorFormat::Oklch] {
+                let text = color.format(format);
+                let again = Color::parse(&text).unwrap();
+                assert_eq!(again.to_rgba8(), color.to_rgba8(), "{} via {}", input, text);
+                assert_eq!(again.format(ColorFormat::Hex), input);
+            }
+        }
+    }
+
+    #[test]
+    fn format_notations() {
+        let color = Color::parse("hsl(213, 76%, 55%)").unwrap();
+        assert_eq!(color.format(ColorFormat::Hex), "#3584e3");
+        assert_eq!(color.format(ColorFormat::Rgb), "rgb(53, 132, 227)");
+        let translucent = color.with_alpha(0.5);
+        assert_eq!(translucent.format(ColorFormat::Hex), "#3584e380");
+        assert_eq!(
+            translucent.format(ColorFormat::Rgb),
+            "rgba(53, 132, 227, 0.5)"
+        );
+        assert!(translucent.format(ColorFormat::Oklch).ends_with(" / 0.5)"));
+        assert_eq!(
+            Color::parse("#808080").unwrap().format(ColorFormat::Oklch),
+            "oklch(59.99% 0 0)"
+        );
+    }
+
+    #[test]
+    fn normalized_rewrites_only_the_text() {
+        let color = Color::parse("rgb(53, 132, 228)").unwrap();
+        let normalized = color.normalized(ColorFormat::Hex);
+        assert_eq!(normalized.as_str(), "#3584e4");
+        assert_eq!(normalized.to_rgba8(), color.to_rgba8());
+    }
+
+    #[test]
+    fn out_of_gamut_oklch_is_clipped_on_output() {
+        let color = Color::parse("oklch(70% 0.4 145)").unwrap();
+        assert!(!color.in_srgb_gamut());
+        let (red, green, blue, _) = color.to_rgba();
+        assert!([red, green, blue].iter().all(|c| (0.0..=1.0).contains(c)));
+        assert!(Color::parse("#3584e4").unwrap().in_srgb_gamut());
+    }
+}
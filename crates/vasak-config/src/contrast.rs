@@ -0,0 +1,284 @@
+//! Contrast audit for color schemes: WCAG 2.x ratios and, optionally, APCA.
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::models::{SchemeData, SchemeVariant, ThemeVariant};
+
+/// Options for [`check_scheme_contrast`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContrastOptions {
+    /// Also compute the APCA lightness contrast (Lc) of each pair.
+    #[serde(default)]
+    pub apca: bool,
+}
+
+/// WCAG 2.x verdicts for one pair.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WcagVerdict {
+    /// Normal text, ratio >= 4.5.
+    pub aa: bool,
+    /// Large text, ratio >= 3.
+    pub aa_large: bool,
+    /// Normal text, ratio >= 7.
+    pub aaa: bool,
+    /// Large text, ratio >= 4.5.
+    pub aaa_large: bool,
+}
+
+impl WcagVerdict {
+    pub fn from_ratio(ratio: f64) -> Self {
+        Self {
+            aa: ratio >= 4.5,
+            aa_large: ratio >= 3.0,
+            aaa: ratio >= 7.0,
+            aaa_large: ratio >= 4.5,
+        }
+    }
+}
+
+/// Contrast of one foreground/background pair of a variant.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContrastPair {
+    pub variant: SchemeVariant,
+    /// Role of the foreground, e.g. `ui.text.main`.
+    pub foreground_role: String,
+    pub background_role: String,
+    pub foreground: Color,
+    pub background: Color,
+    /// WCAG contrast ratio, from 1 to 21.
+    pub ratio: f64,
+    pub wcag: WcagVerdict,
+    /// APCA Lc; positive for dark text on light backgrounds, negative for
+    /// light text on dark ones. Only present when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apca: Option<f64>,
+}
+
+/// Contrast of every audited pair of a scheme.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContrastReport {
+    pub scheme_id: String,
+    /// Whether every pair reaches AA for normal text.
+    pub passes_aa: bool,
+    pub pairs: Vec<ContrastPair>,
+}
+
+/// (text role, background role) pairs audited in each variant.
+fn audited_pairs(variant: &ThemeVariant) -> [(&'static str, &Color, &'static str, &Color); 5] {
+    let ui = &variant.ui;
+    let terminal = &variant.terminal;
+    [
+        (
+            "ui.text.main",
+            &ui.text.main,
+            "ui.background",
+            &ui.background,
+        ),
+        ("ui.text.main", &ui.text.main, "ui.surface", &ui.surface),
+        (
+            "ui.text.muted",
+            &ui.text.muted,
+            "ui.background",
+            &ui.background,
+        ),
+        (
+            "ui.text.on-primary",
+            &ui.text.on_primary,
+            "ui.color.primary",
+            &ui.color.primary,
+        ),
+        (
+            "terminal.foreground",
+            &terminal.foreground,
+            "terminal.background",
+            &terminal.background,
+        ),
+    ]
+}
+
+/// Translucent text is seen blended over its background.
+fn composite(foreground: &Color, background: &Color) -> (f64, f64, f64) {
+    let (fr, fg, fb, alpha) = foreground.to_rgba();
+    let (br, bg, bb, _) = background.to_rgba();
+    (
+        fr * alpha + br * (1.0 - alpha),
+        fg * alpha + bg * (1.0 - alpha),
+        fb * alpha + bb * (1.0 - alpha),
+    )
+}
+
+/// Relative luminance as defined by WCAG 2.x.
+fn relative_luminance((red, green, blue): (f64, f64, f64)) -> f64 {
+    let linear = |channel: f64| {
+        if channel <= 0.03928 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(red) + 0.7152 * linear(green) + 0.0722 * linear(blue)
+}
+
+/// WCAG 2.x contrast ratio of `foreground` over `background`.
+pub fn contrast_ratio(foreground: &Color, background: &Color) -> f64 {
+    let text = relative_luminance(composite(foreground, background));
+    let (br, bg, bb, _) = background.to_rgba();
+    let back = relative_luminance((br, bg, bb));
+    let (lighter, darker) = if text > back {
+        (text, back)
+    } else {
+        (back, text)
+    };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// APCA estimated screen luminance, with the black soft clamp.
+fn apca_luminance((red, green, blue): (f64, f64, f64)) -> f64 {
+    let y = 0.2126729 * red.powf(2.4) + 0.7151522 * green.powf(2.4) + 0.0721750 * blue.powf(2.4);
+    if y < 0.022 {
+        y + (0.022 - y).powf(1.414)
+    } else {
+        y
+    }
+}
+
+/// APCA lightness contrast (Lc) of `foreground` over `background`, using the
+/// APCA-W3 0.0.98G constants.
+pub fn apca_contrast(foreground: &Color, background: &Color) -> f64 {
+    let text = apca_luminance(composite(foreground, background));
+    let (br, bg, bb, _) = background.to_rgba();
+    let back = apca_luminance((br, bg, bb));
+
+    if (back - text).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let lc = if back > text {
+        let sapc = (back.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        let sapc = (back.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+    lc * 100.0
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Audits the text pairs of both variants of `scheme`.
+pub fn check_scheme_contrast(scheme: &SchemeData, options: ContrastOptions) -> ContrastReport {
+    let mut pairs = Vec::new();
+    for variant in SchemeVariant::ALL {
+        for (foreground_role, foreground, background_role, background) in
+            audited_pairs(scheme.colors.variant(variant))
+        {
+            let ratio = contrast_ratio(foreground, background);
+            pairs.push(ContrastPair {
+                variant,
+                foreground_role: foreground_role.to_string(),
+                background_role: background_role.to_string(),
+                foreground: foreground.clone(),
+                background: background.clone(),
+                ratio: round(ratio, 2),
+                wcag: WcagVerdict::from_ratio(ratio),
+                apca: options
+                    .apca
+                    .then(|| round(apca_contrast(foreground, background), 1)),
+            });
+        }
+    }
+
+    ContrastReport {
+        scheme_id: scheme.id.clone(),
+        passes_aa: pairs.iter().all(|pair| pair.wcag.aa),
+        pairs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(input: &str) -> Color {
+        Color::parse(input).unwrap()
+    }
+
+    #[test]
+    fn wcag_ratio_of_known_pairs() {
+        let white = color("#ffffff");
+        let black = color("#000000");
+        assert!((contrast_ratio(&black, &white) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio(&white, &black) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio(&white, &white) - 1.0).abs() < 1e-9);
+        // #767676 is the lightest gray that passes AA on white.
+        assert_eq!(round(contrast_ratio(&color("#767676"), &white), 2), 4.54);
+        assert_eq!(round(contrast_ratio(&color("#777777"), &white), 2), 4.48);
+    }
+
+    #[test]
+    fn translucent_text_is_composited_over_its_background() {
+        let white = color("#ffffff");
+        let half_black = color("rgba(0, 0, 0, 0.5)");
+        let gray = color("rgb(127.5, 127.5, 127.5)");
+        assert!((contrast_ratio(&half_black, &white) - contrast_ratio(&gray, &white)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wcag_verdict_thresholds() {
+        assert_eq!(
+            WcagVerdict::from_ratio(4.5),
+            WcagVerdict {
+                aa: true,
+                aa_large: true,
+                aaa: false,
+                aaa_large: true,
+            }
+        );
+        let low = WcagVerdict::from_ratio(2.99);
+        assert!(!low.aa && !low.aa_large && !low.aaa && !low.aaa_large);
+        assert!(WcagVerdict::from_ratio(7.0).aaa);
+    }
+
+    #[test]
+    fn apca_sign_follows_the_polarity() {
+        let white = color("#ffffff");
+        let black = color("#000000");
+        let dark_on_light = apca_contrast(&black, &white);
+        let light_on_dark = apca_contrast(&white, &black);
+        assert_eq!(round(dark_on_light, 1), 106.0);
+        assert_eq!(round(light_on_dark, 1), -107.9);
+        assert_eq!(apca_contrast(&white, &white), 0.0);
+        assert!(apca_contrast(&color("#767676"), &white) > 0.0);
+        assert!(apca_contrast(&color("#767676"), &black) < 0.0);
+    }
+
+    #[test]
+    fn apca_is_only_reported_when_requested() {
+        let scheme = crate::generate::generate_scheme(
+            &color("#3584e4"),
+            &crate::generate::GenerateOptions::default(),
+        );
+        let report = check_scheme_contrast(&scheme, ContrastOptions::default());
+        assert_eq!(report.pairs.len(), 10);
+        assert!(report.pairs.iter().all(|pair| pair.apca.is_none()));
+
+        let report = check_scheme_contrast(&scheme, ContrastOptions { apca: true });
+        assert!(report.pairs.iter().all(|pair| pair.apca.is_some()));
+    }
+}
@@ -0,0 +1,159 @@
+//! `org.vasak.Config` service on the session bus.
+//!
+//! Values cross the bus as JSON strings, the same representation used by
+//! `vasak.conf` and the Tauri commands.
+
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use zbus::{fdo, interface, object_server::SignalEmitter};
+
+use crate::models::VSKConfig;
+use crate::store::ConfigStore;
+
+pub const DBUS_SERVICE_NAME: &str = "org.vasak.Config";
+pub const DBUS_OBJECT_PATH: &str = "/org/vasak/Config";
+
+/// Called after a change requested over D-Bus has been persisted.
+pub type RemoteChangeListener = Arc<dyn Fn(&VSKConfig) + Send + Sync>;
+
+struct ConfigInterface {
+    store: ConfigStore,
+    on_remote_change: Option<RemoteChangeListener>,
+}
+
+fn failed(e: crate::Error) -> fdo::Error {
+    fdo::Error::Failed(e.to_string())
+}
+
+impl ConfigInterface {
+    fn notify_remote_change(&self, config: &VSKConfig) {
+        if let Some(listener) = self.on_remote_change.as_ref() {
+            listener(config);
+        }
+    }
+}
+
+#[interface(name = "org.vasak.Config")]
+impl ConfigInterface {
+    /// Returns the JSON value at a JSON pointer (`""` for the whole config).
+    async fn get(&self, pointer: &str) -> fdo::Result<String> {
+        let value = self
+            .store
+            .get_value(pointer)
+            .await
+            .map_err(failed)?
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("No value at {}", pointer)))?;
+        serde_json::to_string(&value).map_err(|e| failed(crate::Error::Json(e)))
+    }
+
+    /// Sets the value at a JSON pointer; `value` must be JSON.
+    async fn set(&self, pointer: &str, value: &str) -> fdo::Result<()> {
+        let value = serde_json::from_str(value)
+            .map_err(|e| fdo::Error::InvalidArgs(format!("Invalid JSON value: {}", e)))?;
+        let config = self.store.set_value(pointer, value).await.map_err(failed)?;
+        self.notify_remote_change(&config);
+        Ok(())
+    }
+
+    /// Returns every available scheme as a JSON array.
+    async fn get_schemes(&self) -> fdo::Result<String> {
+        let schemes = self.store.load_schemes().await.map_err(failed)?;
+        serde_json::to_string(&schemes).map_err(|e| failed(crate::Error::Json(e)))
+    }
+
+    /// Persists the dark mode flag and syncs the system theme.
+    async fn set_dark_mode(&self, darkmode: bool) -> fdo::Result<()> {
+        let config = self.store.set_darkmode(darkmode).await.map_err(failed)?;
+        self.notify_remote_change(&config);
+        Ok(())
+    }
+
+    /// Emitted with the full config as JSON whenever it changes.
+    #[zbus(signal)]
+    async fn changed(emitter: &SignalEmitter<'_>, config: &str) -> zbus::Result<()>;
+}
+
+/// A running `org.vasak.Config` service. Dropping it releases the bus name.
+pub struct DbusService {
+    connection: zbus::Connection,
+    forwarder: tokio::task::JoinHandle<()>,
+}
+
+impl DbusService {
+    /// Publishes `store` on the session bus.
+    ///
+    /// Must be called from within a tokio runtime. Fails if another process
+    /// already owns [`DBUS_SERVICE_NAME`].
+    pub async fn start(store: ConfigStore) -> crate::Result<Self> {
+        Self::start_with_listener(store, None).await
+    }
+
+    /// Same as [`Self::start`], calling `on_remote_change` after each change
+    /// made through the bus so the host can refresh its own views.
+    pub async fn start_with_listener(
+        store: ConfigStore,
+        on_remote_change: Option<RemoteChangeListener>,
+    ) -> crate::Result<Self> {
+        let mut changes = store.subscribe_changes();
+        let interface = ConfigInterface {
+            store,
+            on_remote_change,
+        };
+
+        let connection = zbus::connection::Builder::session()?
+            .name(DBUS_SERVICE_NAME)?
+            .serve_at(DBUS_OBJECT_PATH, interface)?
+            .build()
+            .await?;
+
+        let signal_connection = connection.clone();
+        let forwarder = tokio::spawn(async move {
+            let emitter = match SignalEmitter::new(&signal_connection, DBUS_OBJECT_PATH) {
+                Ok(emitter) => emitter,
+                Err(e) => {
+                    eprintln!("[DbusService] Invalid object path: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                let config = match changes.recv().await {
+                    Ok(config) => config,
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("[DbusService] Skipped {} config changes", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                let payload = match serde_json::to_string(&config) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        eprintln!("[DbusService] Failed to serialize config: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Err(e) = ConfigInterface::changed(&emitter, &payload).await {
+                    eprintln!("[DbusService] Failed to emit Changed signal: {}", e);
+                }
+            }
+        });
+
+        Ok(Self {
+            connection,
+            forwarder,
+        })
+    }
+
+    /// The bus connection the service is exported on.
+    pub fn connection(&self) -> &zbus::Connection {
+        &self.connection
+    }
+}
+
+impl Drop for DbusService {
+    fn drop(&mut self) {
+        self.forwarder.abort();
+    }
+}
@@ -8,6 +8,9 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Failed to parse JSON: {0}")]
     Json(#[from] serde_json::Error),
+    #[cfg(feature = "dbus")]
+    #[error("D-Bus error: {0}")]
+    Dbus(#[from] zbus::Error),
     #[error("Other error: {0}")]
     Other(String),
 }
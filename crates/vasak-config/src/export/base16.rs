@@ -0,0 +1,135 @@
+//! base16/base24 YAML and iTerm2 presets, with the importer's slot mapping
+//! in reverse.
+
+use std::fmt::Write;
+
+use super::{mix, opaque_hex, ExportContext};
+use crate::color::Color;
+use crate::models::ThemeVariant;
+
+fn variant<'a>(context: &ExportContext<'a>) -> &'a ThemeVariant {
+    context.scheme.colors.variant(context.variant)
+}
+
+/// The 16 base16 slots. `base06` and `base09`, which Vasak schemes lack, are
+/// mixed from their neighbors; `base0F` is the secondary color.
+fn base16_slots(variant: &ThemeVariant) -> Vec<(&'static str, Color)> {
+    let ui = &variant.ui;
+    let ansi = &variant.terminal.ansi;
+    vec![
+        ("base00", ui.background.clone()),
+        ("base01", ui.surface.clone()),
+        ("base02", ui.border.clone()),
+        ("base03", ansi.bright_black.clone()),
+        ("base04", ui.text.muted.clone()),
+        ("base05", ui.text.main.clone()),
+        ("base06", mix(&ui.text.main, &ansi.bright_white, 0.5)),
+        ("base07", ansi.bright_white.clone()),
+        ("base08", ansi.red.clone()),
+        ("base09", mix(&ansi.red, &ansi.yellow, 0.5)),
+        ("base0A", ansi.yellow.clone()),
+        ("base0B", ansi.green.clone()),
+        ("base0C", ansi.cyan.clone()),
+        ("base0D", ui.color.primary.clone()),
+        ("base0E", ansi.magenta.clone()),
+        ("base0F", ui.color.secondary.clone()),
+    ]
+}
+
+/// Extra base24 slots: darker backgrounds and the bright colors.
+fn base24_slots(variant: &ThemeVariant) -> Vec<(&'static str, Color)> {
+    let ui = &variant.ui;
+    let ansi = &variant.terminal.ansi;
+    let black = Color::from_rgba(0.0, 0.0, 0.0, 1.0);
+    vec![
+        ("base10", mix(&ui.background, &black, 0.2)),
+        ("base11", mix(&ui.background, &black, 0.4)),
+        ("base12", ansi.bright_red.clone()),
+        ("base13", ansi.bright_yellow.clone()),
+        ("base14", ansi.bright_green.clone()),
+        ("base15", ansi.bright_cyan.clone()),
+        ("base16", ansi.bright_blue.clone()),
+        ("base17", ansi.bright_magenta.clone()),
+    ]
+}
+
+/// Double-quoted YAML; only `\` and `"` need escaping.
+fn yaml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn tinted_yaml(context: &ExportContext<'_>, system: &str, base24: bool) -> String {
+    let variant = variant(context);
+    let scheme = context.scheme;
+    let mut slots = base16_slots(variant);
+    if base24 {
+        slots.extend(base24_slots(variant));
+    }
+
+    let mut out = format!("# {}\n", context.header());
+    let _ = writeln!(out, "system: {}", yaml_string(system));
+    let _ = writeln!(out, "name: {}", yaml_string(context.title));
+    let _ = writeln!(out, "slug: {}", yaml_string(&scheme.id));
+    let _ = writeln!(out, "author: {}", yaml_string(&scheme.author));
+    if !scheme.description.is_empty() {
+        let _ = writeln!(out, "description: {}", yaml_string(&scheme.description));
+    }
+    let _ = writeln!(out, "variant: {}", yaml_string(context.variant.as_str()));
+    out.push_str("palette:\n");
+    for (slot, color) in slots {
+        let _ = writeln!(out, "  {}: {}", slot, yaml_string(&opaque_hex(&color)));
+    }
+    out
+}
+
+pub(super) fn base16(context: &ExportContext<'_>) -> String {
+    tinted_yaml(context, "base16", false)
+}
+
+pub(super) fn base24(context: &ExportContext<'_>) -> String {
+    tinted_yaml(context, "base24", true)
+}
+
+fn iterm_color(out: &mut String, key: &str, color: &Color) {
+    let (red, green, blue, alpha) = color.to_rgba();
+    let _ = writeln!(out, "\t<key>{}</key>\n\t<dict>", key);
+    for (component, value) in [
+        ("Alpha", alpha),
+        ("Blue", blue),
+        ("Green", green),
+        ("Red", red),
+    ] {
+        let _ = writeln!(
+            out,
+            "\t\t<key>{} Component</key>\n\t\t<real>{}</real>",
+            component, value
+        );
+    }
+    out.push_str("\t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n\t</dict>\n");
+}
+
+pub(super) fn itermcolors(context: &ExportContext<'_>) -> String {
+    let variant = variant(context);
+    let terminal = &variant.terminal;
+
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+        "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    ));
+    // `--` cannot appear inside an XML comment.
+    let _ = writeln!(out, "<!-- {} -->", context.header().replace("--", "- -"));
+    out.push_str("<plist version=\"1.0\">\n<dict>\n");
+    for (index, color) in terminal.ansi.colors().into_iter().enumerate() {
+        iterm_color(&mut out, &format!("Ansi {} Color", index), color);
+    }
+    iterm_color(&mut out, "Background Color", &terminal.background);
+    iterm_color(&mut out, "Bold Color", &terminal.foreground);
+    iterm_color(&mut out, "Cursor Color", &terminal.cursor);
+    iterm_color(&mut out, "Cursor Text Color", &terminal.background);
+    iterm_color(&mut out, "Foreground Color", &terminal.foreground);
+    iterm_color(&mut out, "Selected Text Color", &terminal.foreground);
+    iterm_color(&mut out, "Selection Color", &variant.ui.border);
+    out.push_str("</dict>\n</plist>\n");
+    out
+}
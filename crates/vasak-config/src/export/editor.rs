@@ -0,0 +1,427 @@
+//! Editor themes: VS Code color themes and Neovim colorschemes.
+
+use std::fmt::Write;
+
+use serde_json::{json, Map, Value};
+
+use super::{mix, opaque_hex, ExportContext};
+use crate::color::{Color, ColorFormat};
+use crate::models::ThemeVariant;
+
+/// Syntax colors, taken from the ANSI palette so the editor matches the
+/// terminal.
+struct Syntax<'a> {
+    comment: &'a Color,
+    keyword: &'a Color,
+    function: &'a Color,
+    kind: &'a Color,
+    string: &'a Color,
+    constant: &'a Color,
+    variable: &'a Color,
+    parameter: &'a Color,
+    tag: &'a Color,
+    attribute: &'a Color,
+    operator: &'a Color,
+    error: &'a Color,
+    warning: &'a Color,
+    info: &'a Color,
+    hint: &'a Color,
+}
+
+impl<'a> Syntax<'a> {
+    fn new(variant: &'a ThemeVariant) -> Self {
+        let ansi = &variant.terminal.ansi;
+        let text = &variant.ui.text;
+        Self {
+            comment: &text.muted,
+            keyword: &ansi.magenta,
+            function: &ansi.blue,
+            kind: &ansi.yellow,
+            string: &ansi.green,
+            constant: &ansi.cyan,
+            variable: &text.main,
+            parameter: &ansi.bright_red,
+            tag: &ansi.red,
+            attribute: &ansi.bright_yellow,
+            operator: &ansi.bright_cyan,
+            error: &ansi.red,
+            warning: &ansi.yellow,
+            info: &ansi.blue,
+            hint: &ansi.cyan,
+        }
+    }
+}
+
+/// VS Code names of the eight ANSI colors.
+const ANSI_NAMES: [&str; 8] = [
+    "Black", "Red", "Green", "Yellow", "Blue", "Magenta", "Cyan", "White",
+];
+
+fn variant<'a>(context: &ExportContext<'a>) -> &'a ThemeVariant {
+    context.scheme.colors.variant(context.variant)
+}
+
+/// VS Code accepts `#rrggbbaa`.
+fn hex(color: &Color) -> String {
+    color.format(ColorFormat::Hex)
+}
+
+fn workbench_colors(variant: &ThemeVariant) -> Map<String, Value> {
+    let ui = &variant.ui;
+    let terminal = &variant.terminal;
+    let syntax = Syntax::new(variant);
+    let primary = &ui.color.primary;
+
+    let mut colors = Map::new();
+    let mut set = |key: &str, color: &Color| {
+        colors.insert(key.to_string(), Value::String(hex(color)));
+    };
+
+    set("foreground", &ui.text.main);
+    set("descriptionForeground", &ui.text.muted);
+    set("errorForeground", syntax.error);
+    set("focusBorder", primary);
+    set("textLink.foreground", primary);
+    set("textLink.activeForeground", &ui.color.secondary);
+    set("widget.border", &ui.border);
+
+    set("button.background", primary);
+    set("button.foreground", &ui.text.on_primary);
+    set("badge.background", primary);
+    set("badge.foreground", &ui.text.on_primary);
+    set("progressBar.background", primary);
+    set("input.background", &ui.surface);
+    set("input.foreground", &ui.text.main);
+    set("input.border", &ui.border);
+    set("input.placeholderForeground", &ui.text.muted);
+    set("dropdown.background", &ui.surface);
+    set("dropdown.foreground", &ui.text.main);
+    set("dropdown.border", &ui.border);
+
+    set("list.activeSelectionBackground", primary);
+    set("list.activeSelectionForeground", &ui.text.on_primary);
+    set("list.inactiveSelectionBackground", &primary.with_alpha(0.2));
+    set("list.hoverBackground", &primary.with_alpha(0.1));
+    set("list.highlightForeground", primary);
+
+    set("titleBar.activeBackground", &ui.surface);
+    set("titleBar.activeForeground", &ui.text.main);
+    set("titleBar.inactiveBackground", &ui.background);
+    set("titleBar.inactiveForeground", &ui.text.muted);
+    set("activityBar.background", &ui.surface);
+    set("activityBar.foreground", &ui.text.main);
+    set("activityBar.inactiveForeground", &ui.text.muted);
+    set("activityBar.border", &ui.border);
+    set("activityBarBadge.background", primary);
+    set("activityBarBadge.foreground", &ui.text.on_primary);
+    set("sideBar.background", &ui.surface);
+    set("sideBar.foreground", &ui.text.main);
+    set("sideBar.border", &ui.border);
+    set("sideBarSectionHeader.background", &ui.surface);
+    set("statusBar.background", &ui.surface);
+    set("statusBar.foreground", &ui.text.main);
+    set("statusBar.border", &ui.border);
+    set("panel.background", &ui.background);
+    set("panel.border", &ui.border);
+    set("editorGroup.border", &ui.border);
+    set("editorGroupHeader.tabsBackground", &ui.surface);
+    set("tab.activeBackground", &ui.background);
+    set("tab.activeForeground", &ui.text.main);
+    set("tab.inactiveBackground", &ui.surface);
+    set("tab.inactiveForeground", &ui.text.muted);
+    set("tab.activeBorderTop", primary);
+    set("tab.border", &ui.border);
+
+    set("editor.background", &ui.background);
+    set("editor.foreground", &ui.text.main);
+    set("editor.selectionBackground", &primary.with_alpha(0.3));
+    set(
+        "editor.lineHighlightBackground",
+        &ui.text.main.with_alpha(0.05),
+    );
+    set(
+        "editor.findMatchBackground",
+        &syntax.warning.with_alpha(0.4),
+    );
+    set("editorCursor.foreground", &terminal.cursor);
+    set("editorLineNumber.foreground", &ui.text.muted);
+    set("editorLineNumber.activeForeground", &ui.text.main);
+    set("editorWhitespace.foreground", &ui.border);
+    set("editorIndentGuide.background1", &ui.border);
+    set("editorIndentGuide.activeBackground1", &ui.text.muted);
+    set("editorWidget.background", &ui.surface);
+    set("editorWidget.border", &ui.border);
+    set("editorError.foreground", syntax.error);
+    set("editorWarning.foreground", syntax.warning);
+    set("editorInfo.foreground", syntax.info);
+    set("editorHint.foreground", syntax.hint);
+    set("gitDecoration.addedResourceForeground", syntax.string);
+    set("gitDecoration.modifiedResourceForeground", syntax.warning);
+    set("gitDecoration.deletedResourceForeground", syntax.error);
+
+    set("terminal.background", &terminal.background);
+    set("terminal.foreground", &terminal.foreground);
+    set("terminalCursor.foreground", &terminal.cursor);
+    let ansi = terminal.ansi.colors();
+    for (index, name) in ANSI_NAMES.iter().enumerate() {
+        set(&format!("terminal.ansi{}", name), ansi[index]);
+        set(&format!("terminal.ansiBright{}", name), ansi[index + 8]);
+    }
+    colors
+}
+
+fn token_colors(syntax: &Syntax<'_>) -> Vec<Value> {
+    let rule = |scopes: &[&str], color: &Color| json!({ "scope": scopes, "settings": { "foreground": hex(color) } });
+    vec![
+        json!({
+            "scope": ["comment", "punctuation.definition.comment"],
+            "settings": { "foreground": hex(syntax.comment), "fontStyle": "italic" }
+        }),
+        rule(
+            &[
+                "keyword",
+                "storage.type",
+                "storage.modifier",
+                "keyword.control",
+            ],
+            syntax.keyword,
+        ),
+        rule(
+            &["keyword.operator", "punctuation.accessor"],
+            syntax.operator,
+        ),
+        rule(
+            &[
+                "entity.name.function",
+                "support.function",
+                "meta.function-call",
+            ],
+            syntax.function,
+        ),
+        rule(
+            &[
+                "entity.name.type",
+                "entity.name.class",
+                "support.type",
+                "support.class",
+                "storage.type.primitive",
+            ],
+            syntax.kind,
+        ),
+        rule(
+            &["string", "string.quoted", "markup.inline.raw"],
+            syntax.string,
+        ),
+        rule(
+            &[
+                "constant.numeric",
+                "constant.language",
+                "constant.character",
+                "constant.other",
+                "variable.other.constant",
+            ],
+            syntax.constant,
+        ),
+        rule(&["variable", "meta.definition.variable"], syntax.variable),
+        rule(&["variable.parameter"], syntax.parameter),
+        rule(&["entity.name.tag"], syntax.tag),
+        rule(&["entity.other.attribute-name"], syntax.attribute),
+        rule(&["invalid", "invalid.illegal"], syntax.error),
+        rule(&["markup.heading"], syntax.function),
+        rule(&["markup.inserted"], syntax.string),
+        rule(&["markup.changed"], syntax.warning),
+        rule(&["markup.deleted"], syntax.error),
+    ]
+}
+
+pub(super) fn vscode(context: &ExportContext<'_>) -> String {
+    let variant = variant(context);
+    // JSON has no comments; the header goes in a key VS Code ignores.
+    let theme = json!({
+        "$schema": "vscode://schemas/color-theme",
+        "$comment": context.header(),
+        "name": context.title,
+        "type": context.variant.as_str(),
+        "semanticHighlighting": true,
+        "colors": workbench_colors(variant),
+        "tokenColors": token_colors(&Syntax::new(variant)),
+    });
+    let mut out = serde_json::to_string_pretty(&theme).expect("a JSON value always serializes");
+    out.push('\n');
+    out
+}
+
+/// `{ fg = "#...", bg = "#...", ... }` for `nvim_set_hl`.
+struct Highlight {
+    fg: Option<String>,
+    bg: Option<String>,
+    sp: Option<String>,
+    attributes: &'static [&'static str],
+}
+
+impl Highlight {
+    fn fg(color: &Color) -> Self {
+        Self {
+            fg: Some(opaque_hex(color)),
+            bg: None,
+            sp: None,
+            attributes: &[],
+        }
+    }
+
+    fn colors(fg: &Color, bg: &Color) -> Self {
+        Self {
+            bg: Some(opaque_hex(bg)),
+            ..Self::fg(fg)
+        }
+    }
+
+    fn bg(color: &Color) -> Self {
+        Self {
+            fg: None,
+            ..Self::colors(color, color)
+        }
+    }
+
+    fn undercurl(color: &Color) -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            sp: Some(opaque_hex(color)),
+            attributes: &["undercurl"],
+        }
+    }
+
+    fn with(self, attributes: &'static [&'static str]) -> Self {
+        Self { attributes, ..self }
+    }
+
+    fn lua(&self) -> String {
+        let mut fields = Vec::new();
+        for (key, value) in [("fg", &self.fg), ("bg", &self.bg), ("sp", &self.sp)] {
+            if let Some(value) = value {
+                fields.push(format!("{} = \"{}\"", key, value));
+            }
+        }
+        for attribute in self.attributes {
+            fields.push(format!("{} = true", attribute));
+        }
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
+
+fn neovim_groups(variant: &ThemeVariant) -> Vec<(&'static str, Highlight)> {
+    let ui = &variant.ui;
+    let text = &ui.text;
+    let primary = &ui.color.primary;
+    let syntax = Syntax::new(variant);
+    let cursor_line = mix(&ui.background, &text.main, 0.05);
+    let selection = mix(&ui.background, primary, 0.3);
+    let diff = |color: &Color| Highlight::bg(&mix(&ui.background, color, 0.2));
+
+    vec![
+        ("Normal", Highlight::colors(&text.main, &ui.background)),
+        ("NormalFloat", Highlight::colors(&text.main, &ui.surface)),
+        ("FloatBorder", Highlight::colors(&ui.border, &ui.surface)),
+        (
+            "Cursor",
+            Highlight::colors(&ui.background, &variant.terminal.cursor),
+        ),
+        ("CursorLine", Highlight::bg(&cursor_line)),
+        ("ColorColumn", Highlight::bg(&cursor_line)),
+        ("CursorLineNr", Highlight::fg(&text.main).with(&["bold"])),
+        ("LineNr", Highlight::fg(&text.muted)),
+        ("SignColumn", Highlight::fg(&text.muted)),
+        ("Visual", Highlight::bg(&selection)),
+        ("Search", Highlight::colors(&ui.background, syntax.warning)),
+        ("IncSearch", Highlight::colors(&text.on_primary, primary)),
+        ("MatchParen", Highlight::fg(primary).with(&["bold"])),
+        ("Pmenu", Highlight::colors(&text.main, &ui.surface)),
+        ("PmenuSel", Highlight::colors(&text.on_primary, primary)),
+        ("StatusLine", Highlight::colors(&text.main, &ui.surface)),
+        ("StatusLineNC", Highlight::colors(&text.muted, &ui.surface)),
+        ("TabLine", Highlight::colors(&text.muted, &ui.surface)),
+        ("TabLineSel", Highlight::colors(&text.main, &ui.background)),
+        ("TabLineFill", Highlight::bg(&ui.surface)),
+        ("WinSeparator", Highlight::fg(&ui.border)),
+        ("Folded", Highlight::colors(&text.muted, &ui.surface)),
+        ("NonText", Highlight::fg(&ui.border)),
+        ("Whitespace", Highlight::fg(&ui.border)),
+        ("Directory", Highlight::fg(syntax.function)),
+        ("Title", Highlight::fg(primary).with(&["bold"])),
+        ("ErrorMsg", Highlight::fg(syntax.error)),
+        ("WarningMsg", Highlight::fg(syntax.warning)),
+        ("ModeMsg", Highlight::fg(&text.main).with(&["bold"])),
+        ("MoreMsg", Highlight::fg(primary)),
+        ("Question", Highlight::fg(primary)),
+        ("Comment", Highlight::fg(syntax.comment).with(&["italic"])),
+        ("Constant", Highlight::fg(syntax.constant)),
+        ("String", Highlight::fg(syntax.string)),
+        ("Character", Highlight::fg(syntax.string)),
+        ("Identifier", Highlight::fg(syntax.variable)),
+        ("Function", Highlight::fg(syntax.function)),
+        ("Statement", Highlight::fg(syntax.keyword)),
+        ("Keyword", Highlight::fg(syntax.keyword)),
+        ("Operator", Highlight::fg(syntax.operator)),
+        ("PreProc", Highlight::fg(syntax.keyword)),
+        ("Type", Highlight::fg(syntax.kind)),
+        ("Special", Highlight::fg(syntax.attribute)),
+        ("Delimiter", Highlight::fg(&text.muted)),
+        ("Tag", Highlight::fg(syntax.tag)),
+        ("Underlined", Highlight::fg(primary).with(&["underline"])),
+        ("Error", Highlight::fg(syntax.error)),
+        (
+            "Todo",
+            Highlight::colors(&ui.background, syntax.warning).with(&["bold"]),
+        ),
+        ("@variable.parameter", Highlight::fg(syntax.parameter)),
+        ("@tag.attribute", Highlight::fg(syntax.attribute)),
+        ("DiagnosticError", Highlight::fg(syntax.error)),
+        ("DiagnosticWarn", Highlight::fg(syntax.warning)),
+        ("DiagnosticInfo", Highlight::fg(syntax.info)),
+        ("DiagnosticHint", Highlight::fg(syntax.hint)),
+        (
+            "DiagnosticUnderlineError",
+            Highlight::undercurl(syntax.error),
+        ),
+        (
+            "DiagnosticUnderlineWarn",
+            Highlight::undercurl(syntax.warning),
+        ),
+        ("DiagnosticUnderlineInfo", Highlight::undercurl(syntax.info)),
+        ("DiagnosticUnderlineHint", Highlight::undercurl(syntax.hint)),
+        ("DiffAdd", diff(syntax.string)),
+        ("DiffChange", diff(syntax.warning)),
+        ("DiffDelete", diff(syntax.error)),
+        (
+            "DiffText",
+            Highlight::bg(&mix(&ui.background, syntax.warning, 0.4)),
+        ),
+    ]
+}
+
+pub(super) fn neovim(context: &ExportContext<'_>) -> String {
+    let variant = variant(context);
+    // `:colorscheme` uses the file name; the id is the suggested one.
+    let name = &context.scheme.id;
+
+    let mut out = format!("-- {}\n\n", context.header());
+    out.push_str("vim.cmd(\"highlight clear\")\n");
+    out.push_str("if vim.fn.exists(\"syntax_on\") == 1 then\n  vim.cmd(\"syntax reset\")\nend\n");
+    let _ = writeln!(out, "vim.o.background = \"{}\"", context.variant.as_str());
+    let _ = writeln!(out, "vim.g.colors_name = \"{}\"\n", name);
+    out.push_str("local hl = vim.api.nvim_set_hl\n");
+    for (group, highlight) in neovim_groups(variant) {
+        let _ = writeln!(out, "hl(0, \"{}\", {})", group, highlight.lua());
+    }
+    out.push('\n');
+    for (index, color) in variant.terminal.ansi.colors().into_iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "vim.g.terminal_color_{} = \"{}\"",
+            index,
+            opaque_hex(color)
+        );
+    }
+    out
+}
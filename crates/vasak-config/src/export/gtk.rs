@@ -0,0 +1,88 @@
+//! `@define-color` overrides for GTK 3 and GTK 4 / libadwaita.
+
+use std::fmt::Write;
+
+use super::ExportContext;
+use crate::color::{Color, ColorFormat};
+use crate::models::ThemeVariant;
+
+/// Every GTK 3 version accepts `rgb()` and `rgba()`, but not 8-digit hex.
+fn css(color: &Color) -> String {
+    color.format(ColorFormat::Rgb)
+}
+
+/// libadwaita named colors. GTK 3 uses them too with themes such as
+/// adw-gtk3.
+fn adwaita_colors(variant: &ThemeVariant) -> Vec<(&'static str, &Color)> {
+    let ui = &variant.ui;
+    let ansi = &variant.terminal.ansi;
+    vec![
+        ("accent_color", &ui.color.primary),
+        ("accent_bg_color", &ui.color.primary),
+        ("accent_fg_color", &ui.text.on_primary),
+        ("window_bg_color", &ui.background),
+        ("window_fg_color", &ui.text.main),
+        ("view_bg_color", &ui.surface),
+        ("view_fg_color", &ui.text.main),
+        ("headerbar_bg_color", &ui.surface),
+        ("headerbar_fg_color", &ui.text.main),
+        ("headerbar_border_color", &ui.border),
+        ("headerbar_backdrop_color", &ui.background),
+        ("sidebar_bg_color", &ui.surface),
+        ("sidebar_fg_color", &ui.text.main),
+        ("card_bg_color", &ui.surface),
+        ("card_fg_color", &ui.text.main),
+        ("dialog_bg_color", &ui.surface),
+        ("dialog_fg_color", &ui.text.main),
+        ("popover_bg_color", &ui.surface),
+        ("popover_fg_color", &ui.text.main),
+        ("destructive_color", &ansi.red),
+        ("error_color", &ansi.red),
+        ("warning_color", &ansi.yellow),
+        ("success_color", &ansi.green),
+    ]
+}
+
+/// Classic Adwaita names in GTK 3.
+fn gtk3_colors(variant: &ThemeVariant) -> Vec<(&'static str, &Color)> {
+    let ui = &variant.ui;
+    vec![
+        ("theme_fg_color", &ui.text.main),
+        ("theme_text_color", &ui.text.main),
+        ("theme_bg_color", &ui.background),
+        ("theme_base_color", &ui.surface),
+        ("theme_selected_bg_color", &ui.color.primary),
+        ("theme_selected_fg_color", &ui.text.on_primary),
+        ("theme_unfocused_fg_color", &ui.text.main),
+        ("theme_unfocused_text_color", &ui.text.main),
+        ("theme_unfocused_bg_color", &ui.background),
+        ("theme_unfocused_base_color", &ui.surface),
+        ("theme_unfocused_selected_bg_color", &ui.color.primary),
+        ("theme_unfocused_selected_fg_color", &ui.text.on_primary),
+        ("insensitive_fg_color", &ui.text.muted),
+        ("insensitive_bg_color", &ui.background),
+        ("insensitive_base_color", &ui.surface),
+        ("borders", &ui.border),
+        ("unfocused_borders", &ui.border),
+    ]
+}
+
+fn define_colors(context: &ExportContext<'_>, colors: &[(&str, &Color)]) -> String {
+    let mut out = format!("/* {} */\n", context.header());
+    for (name, color) in colors {
+        let _ = writeln!(out, "@define-color {} {};", name, css(color));
+    }
+    out
+}
+
+pub(super) fn gtk3(context: &ExportContext<'_>) -> String {
+    let variant = context.scheme.colors.variant(context.variant);
+    let mut colors = gtk3_colors(variant);
+    colors.extend(adwaita_colors(variant));
+    define_colors(context, &colors)
+}
+
+pub(super) fn gtk4(context: &ExportContext<'_>) -> String {
+    let variant = context.scheme.colors.variant(context.variant);
+    define_colors(context, &adwaita_colors(variant))
+}
@@ -0,0 +1,208 @@
+//! Renders color schemes in the theme formats of other applications.
+
+mod base16;
+mod editor;
+mod gtk;
+mod qt;
+mod terminal;
+mod x11;
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::models::{SchemeData, SchemeVariant, VSKConfig};
+
+/// A theme format [`export_scheme`] can produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportFormat {
+    /// Alacritty TOML, for `general.import`.
+    Alacritty,
+    /// Kitty config, for `include`.
+    Kitty,
+    /// foot INI, for `include`.
+    Foot,
+    /// WezTerm color scheme TOML, for `~/.config/wezterm/colors`.
+    Wezterm,
+    /// GNOME Terminal profile as a dconf keyfile, for `dconf load`.
+    GnomeTerminal,
+    /// GTK 3 `@define-color` overrides, for `~/.config/gtk-3.0/gtk.css`.
+    Gtk3,
+    /// GTK 4 / libadwaita `@define-color` overrides, for
+    /// `~/.config/gtk-4.0/gtk.css`.
+    Gtk4,
+    /// KDE color scheme, for `~/.local/share/color-schemes`.
+    Kde,
+    /// qt5ct palette, for `~/.config/qt5ct/colors`.
+    Qt5ct,
+    /// qt6ct palette, for `~/.config/qt6ct/colors`.
+    Qt6ct,
+    /// VS Code color theme JSON, for an extension's `themes` folder.
+    Vscode,
+    /// Neovim Lua colorscheme, for `~/.config/nvim/colors`.
+    Neovim,
+    /// base16 YAML (tinted-theming layout).
+    Base16,
+    /// base24 YAML: base16 plus darker backgrounds and bright colors.
+    Base24,
+    /// iTerm2 color preset.
+    Itermcolors,
+    /// X resources for xterm-like terminals, for `~/.Xresources`.
+    Xresources,
+    /// GTK theme, icon theme and fonts as XSETTINGS, for
+    /// `~/.config/xsettingsd/xsettingsd.conf`.
+    Xsettingsd,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 17] = [
+        ExportFormat::Alacritty,
+        ExportFormat::Kitty,
+        ExportFormat::Foot,
+        ExportFormat::Wezterm,
+        ExportFormat::GnomeTerminal,
+        ExportFormat::Gtk3,
+        ExportFormat::Gtk4,
+        ExportFormat::Kde,
+        ExportFormat::Qt5ct,
+        ExportFormat::Qt6ct,
+        ExportFormat::Vscode,
+        ExportFormat::Neovim,
+        ExportFormat::Base16,
+        ExportFormat::Base24,
+        ExportFormat::Itermcolors,
+        ExportFormat::Xresources,
+        ExportFormat::Xsettingsd,
+    ];
+
+    /// Formats for terminal emulators, built from `ThemeVariant.terminal`.
+    pub const TERMINALS: [ExportFormat; 5] = [
+        ExportFormat::Alacritty,
+        ExportFormat::Kitty,
+        ExportFormat::Foot,
+        ExportFormat::Wezterm,
+        ExportFormat::GnomeTerminal,
+    ];
+
+    /// Formats for GTK, built from `ThemeVariant.ui`.
+    pub const GTK: [ExportFormat; 2] = [ExportFormat::Gtk3, ExportFormat::Gtk4];
+
+    /// Formats for KDE and Qt, built from `ThemeVariant.ui`.
+    pub const QT: [ExportFormat; 3] = [ExportFormat::Kde, ExportFormat::Qt5ct, ExportFormat::Qt6ct];
+
+    /// Formats for X11 applications: terminal colors as X resources, and the
+    /// GTK theme, icons and fonts through xsettingsd.
+    pub const X11: [ExportFormat; 2] = [ExportFormat::Xresources, ExportFormat::Xsettingsd];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::Alacritty => "alacritty",
+            ExportFormat::Kitty => "kitty",
+            ExportFormat::Foot => "foot",
+            ExportFormat::Wezterm => "wezterm",
+            ExportFormat::GnomeTerminal => "gnome-terminal",
+            ExportFormat::Gtk3 => "gtk3",
+            ExportFormat::Gtk4 => "gtk4",
+            ExportFormat::Kde => "kde",
+            ExportFormat::Qt5ct => "qt5ct",
+            ExportFormat::Qt6ct => "qt6ct",
+            ExportFormat::Vscode => "vscode",
+            ExportFormat::Neovim => "neovim",
+            ExportFormat::Base16 => "base16",
+            ExportFormat::Base24 => "base24",
+            ExportFormat::Itermcolors => "itermcolors",
+            ExportFormat::Xresources => "xresources",
+            ExportFormat::Xsettingsd => "xsettingsd",
+        }
+    }
+
+    pub fn is_terminal(self) -> bool {
+        Self::TERMINALS.contains(&self)
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = crate::Error;
+
+    fn from_str(value: &str) -> crate::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| crate::Error::Other(format!("Unknown export format: {}", value)))
+    }
+}
+
+/// What every format needs to render a variant.
+pub(crate) struct ExportContext<'a> {
+    pub scheme: &'a SchemeData,
+    pub variant: SchemeVariant,
+    /// Name the application lists the theme under.
+    pub title: &'a str,
+    /// Active config, where xsettingsd takes icons and fonts from.
+    pub config: Option<&'a VSKConfig>,
+}
+
+impl ExportContext<'_> {
+    fn header(&self) -> String {
+        format!(
+            "Generated by Vasak from the color scheme {} ({}).",
+            self.scheme.id,
+            self.variant.as_str()
+        )
+    }
+}
+
+/// `#rrggbb`: terminal formats do not support transparency.
+pub(crate) fn opaque_hex(color: &Color) -> String {
+    let [red, green, blue, _] = color.to_rgba8();
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+/// `amount` of `other` mixed over `base`, opaque, for formats that need
+/// intermediate shades the scheme does not define.
+pub(crate) fn mix(base: &Color, other: &Color, amount: f64) -> Color {
+    let [br, bg, bb, _] = base.to_rgba8();
+    let [or, og, ob, _] = other.to_rgba8();
+    let channel = |base: u8, other: u8| {
+        (f64::from(base) + (f64::from(other) - f64::from(base)) * amount).round() / 255.0
+    };
+    Color::from_rgba(channel(br, or), channel(bg, og), channel(bb, ob), 1.0)
+}
+
+pub(crate) fn render(context: &ExportContext<'_>, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Alacritty => terminal::alacritty(context),
+        ExportFormat::Kitty => terminal::kitty(context),
+        ExportFormat::Foot => terminal::foot(context),
+        ExportFormat::Wezterm => terminal::wezterm(context),
+        ExportFormat::GnomeTerminal => terminal::gnome_terminal(context),
+        ExportFormat::Gtk3 => gtk::gtk3(context),
+        ExportFormat::Gtk4 => gtk::gtk4(context),
+        ExportFormat::Kde => qt::kde(context),
+        ExportFormat::Qt5ct => qt::qt5ct(context),
+        ExportFormat::Qt6ct => qt::qt6ct(context),
+        ExportFormat::Vscode => editor::vscode(context),
+        ExportFormat::Neovim => editor::neovim(context),
+        ExportFormat::Base16 => base16::base16(context),
+        ExportFormat::Base24 => base16::base24(context),
+        ExportFormat::Itermcolors => base16::itermcolors(context),
+        ExportFormat::Xresources => x11::xresources(context),
+        ExportFormat::Xsettingsd => x11::xsettingsd(context),
+    }
+}
+
+/// Renders the `variant` of `scheme` in `format`.
+///
+/// Without a config, xsettingsd output only names the GTK theme;
+/// `ConfigStore::export_scheme` adds the icon theme and fonts.
+pub fn export_scheme(scheme: &SchemeData, variant: SchemeVariant, format: ExportFormat) -> String {
+    render(
+        &ExportContext {
+            scheme,
+            variant,
+            title: &scheme.name,
+            config: None,
+        },
+        format,
+    )
+}
@@ -0,0 +1,194 @@
+//! KDE color schemes and qt5ct/qt6ct palettes, from `ThemeVariant.ui`.
+
+use std::fmt::Write;
+
+use super::{mix, ExportContext};
+use crate::color::Color;
+use crate::models::ThemeVariant;
+
+/// Qt palettes do not support transparency.
+fn rgb(color: &Color) -> [u8; 3] {
+    let [red, green, blue, _] = color.to_rgba8();
+    [red, green, blue]
+}
+
+fn mixed(base: &Color, other: &Color, amount: f64) -> [u8; 3] {
+    rgb(&mix(base, other, amount))
+}
+
+fn variant<'a>(context: &ExportContext<'a>) -> &'a ThemeVariant {
+    context.scheme.colors.variant(context.variant)
+}
+
+/// A KDE `[Colors:*]` group: backgrounds, texts and decorations.
+struct KdeGroup {
+    name: &'static str,
+    background: [u8; 3],
+    alternate: [u8; 3],
+    foreground: [u8; 3],
+    inactive: [u8; 3],
+    active: [u8; 3],
+    link: [u8; 3],
+    visited: [u8; 3],
+}
+
+fn kde_groups(variant: &ThemeVariant) -> Vec<KdeGroup> {
+    let ui = &variant.ui;
+    let group = |name, background: &Color, alternate: [u8; 3]| KdeGroup {
+        name,
+        background: rgb(background),
+        alternate,
+        foreground: rgb(&ui.text.main),
+        inactive: rgb(&ui.text.muted),
+        active: rgb(&ui.color.primary),
+        link: rgb(&ui.color.primary),
+        visited: rgb(&ui.color.secondary),
+    };
+    let on_primary = rgb(&ui.text.on_primary);
+
+    vec![
+        group("Button", &ui.surface, rgb(&ui.background)),
+        group("Complementary", &ui.background, rgb(&ui.surface)),
+        group("Header", &ui.background, rgb(&ui.surface)),
+        KdeGroup {
+            name: "Selection",
+            background: rgb(&ui.color.primary),
+            alternate: mixed(&ui.color.primary, &ui.background, 0.3),
+            foreground: on_primary,
+            inactive: on_primary,
+            active: on_primary,
+            link: on_primary,
+            visited: on_primary,
+        },
+        group("Tooltip", &ui.surface, rgb(&ui.background)),
+        group("View", &ui.surface, mixed(&ui.surface, &ui.text.main, 0.04)),
+        group("Window", &ui.background, rgb(&ui.surface)),
+    ]
+}
+
+/// `r,g,b`, as KDE writes them.
+fn kde_color([red, green, blue]: [u8; 3]) -> String {
+    format!("{},{},{}", red, green, blue)
+}
+
+pub(super) fn kde(context: &ExportContext<'_>) -> String {
+    let variant = variant(context);
+    let ansi = &variant.terminal.ansi;
+    let focus = kde_color(rgb(&variant.ui.color.primary));
+
+    let mut out = format!("# {}\n", context.header());
+    for group in kde_groups(variant) {
+        let _ = writeln!(out, "\n[Colors:{}]", group.name);
+        for (key, color) in [
+            ("BackgroundAlternate", group.alternate),
+            ("BackgroundNormal", group.background),
+        ] {
+            let _ = writeln!(out, "{}={}", key, kde_color(color));
+        }
+        let _ = writeln!(out, "DecorationFocus={}", focus);
+        let _ = writeln!(out, "DecorationHover={}", focus);
+        for (key, color) in [
+            ("ForegroundActive", group.active),
+            ("ForegroundInactive", group.inactive),
+            ("ForegroundLink", group.link),
+            ("ForegroundNegative", rgb(&ansi.red)),
+            ("ForegroundNeutral", rgb(&ansi.yellow)),
+            ("ForegroundNormal", group.foreground),
+            ("ForegroundPositive", rgb(&ansi.green)),
+            ("ForegroundVisited", group.visited),
+        ] {
+            let _ = writeln!(out, "{}={}", key, kde_color(color));
+        }
+    }
+
+    let ui = &variant.ui;
+    let _ = writeln!(out, "\n[General]");
+    let _ = writeln!(out, "ColorScheme={}", context.title);
+    let _ = writeln!(out, "Name={}", context.title);
+    let _ = writeln!(out, "\n[WM]");
+    for (key, color) in [
+        ("activeBackground", &ui.background),
+        ("activeBlend", &ui.text.main),
+        ("activeForeground", &ui.text.main),
+        ("inactiveBackground", &ui.background),
+        ("inactiveBlend", &ui.text.muted),
+        ("inactiveForeground", &ui.text.muted),
+    ] {
+        let _ = writeln!(out, "{}={}", key, kde_color(rgb(color)));
+    }
+    out
+}
+
+/// Colors in `QPalette::ColorRole` order, from `WindowText` to
+/// `PlaceholderText`, plus `Accent` in Qt 6.
+fn palette(variant: &ThemeVariant, disabled: bool, accent: bool) -> Vec<[u8; 3]> {
+    let ui = &variant.ui;
+    let text = if disabled {
+        mixed(&ui.text.main, &ui.background, 0.5)
+    } else {
+        rgb(&ui.text.main)
+    };
+    let highlight = if disabled {
+        mixed(&ui.color.primary, &ui.background, 0.5)
+    } else {
+        rgb(&ui.color.primary)
+    };
+    // Bevel shades of classic widgets, always towards white and black.
+    let white = Color::from_rgba(1.0, 1.0, 1.0, 1.0);
+    let black = Color::from_rgba(0.0, 0.0, 0.0, 1.0);
+
+    let mut colors = vec![
+        text,                                    // WindowText
+        rgb(&ui.surface),                        // Button
+        mixed(&ui.surface, &white, 0.15),        // Light
+        mixed(&ui.surface, &white, 0.08),        // Midlight
+        mixed(&ui.background, &black, 0.35),     // Dark
+        mixed(&ui.background, &black, 0.2),      // Mid
+        text,                                    // Text
+        rgb(&ui.background),                     // BrightText
+        text,                                    // ButtonText
+        rgb(&ui.surface),                        // Base
+        rgb(&ui.background),                     // Window
+        mixed(&ui.background, &black, 0.6),      // Shadow
+        highlight,                               // Highlight
+        rgb(&ui.text.on_primary),                // HighlightedText
+        rgb(&ui.color.primary),                  // Link
+        rgb(&ui.color.secondary),                // LinkVisited
+        mixed(&ui.surface, &ui.text.main, 0.04), // AlternateBase
+        rgb(&ui.background),                     // NoRole
+        rgb(&ui.surface),                        // ToolTipBase
+        rgb(&ui.text.main),                      // ToolTipText
+        rgb(&ui.text.muted),                     // PlaceholderText
+    ];
+    if accent {
+        colors.push(highlight);
+    }
+    colors
+}
+
+fn qtct(context: &ExportContext<'_>, accent: bool) -> String {
+    let variant = variant(context);
+    // qt5ct and qt6ct read `#aarrggbb`.
+    let row = |disabled: bool| {
+        palette(variant, disabled, accent)
+            .iter()
+            .map(|[red, green, blue]| format!("#ff{:02x}{:02x}{:02x}", red, green, blue))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let active = row(false);
+
+    let mut out = format!("# {}\n\n[ColorScheme]\n", context.header());
+    let _ = writeln!(out, "active_colors={}", active);
+    let _ = writeln!(out, "disabled_colors={}", row(true));
+    let _ = writeln!(out, "inactive_colors={}", active);
+    out
+}
+
+pub(super) fn qt5ct(context: &ExportContext<'_>) -> String {
+    qtct(context, false)
+}
+
+pub(super) fn qt6ct(context: &ExportContext<'_>) -> String {
+    qtct(context, true)
+}
@@ -0,0 +1,130 @@
+//! Terminal emulator themes, from `ThemeVariant.terminal`.
+
+use std::fmt::Write;
+
+use super::{opaque_hex, ExportContext};
+use crate::models::TerminalColors;
+
+/// Alacritty names of the eight colors of each group.
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn terminal<'a>(context: &ExportContext<'a>) -> &'a TerminalColors {
+    &context.scheme.colors.variant(context.variant).terminal
+}
+
+fn palette(terminal: &TerminalColors) -> Vec<String> {
+    terminal.ansi.colors().into_iter().map(opaque_hex).collect()
+}
+
+pub(super) fn alacritty(context: &ExportContext<'_>) -> String {
+    let terminal = terminal(context);
+    let palette = palette(terminal);
+    let background = opaque_hex(&terminal.background);
+
+    let mut out = format!("# {}\n\n", context.header());
+    let _ = writeln!(out, "[colors.primary]");
+    let _ = writeln!(out, "foreground = \"{}\"", opaque_hex(&terminal.foreground));
+    let _ = writeln!(out, "background = \"{}\"\n", background);
+    let _ = writeln!(out, "[colors.cursor]");
+    let _ = writeln!(out, "text = \"{}\"", background);
+    let _ = writeln!(out, "cursor = \"{}\"", opaque_hex(&terminal.cursor));
+    for (section, colors) in [("normal", &palette[..8]), ("bright", &palette[8..])] {
+        let _ = writeln!(out, "\n[colors.{}]", section);
+        for (name, color) in ANSI_NAMES.iter().zip(colors) {
+            let _ = writeln!(out, "{} = \"{}\"", name, color);
+        }
+    }
+    out
+}
+
+pub(super) fn kitty(context: &ExportContext<'_>) -> String {
+    let terminal = terminal(context);
+    let background = opaque_hex(&terminal.background);
+
+    let mut out = format!("# {}\n\n", context.header());
+    let _ = writeln!(out, "foreground {}", opaque_hex(&terminal.foreground));
+    let _ = writeln!(out, "background {}", background);
+    let _ = writeln!(out, "cursor {}", opaque_hex(&terminal.cursor));
+    let _ = writeln!(out, "cursor_text_color {}", background);
+    for (index, color) in palette(terminal).iter().enumerate() {
+        let _ = writeln!(out, "color{} {}", index, color);
+    }
+    out
+}
+
+pub(super) fn foot(context: &ExportContext<'_>) -> String {
+    let terminal = terminal(context);
+    // foot expects colors without `#`.
+    let bare = |color: &str| color.trim_start_matches('#').to_string();
+    let palette: Vec<String> = palette(terminal).iter().map(|color| bare(color)).collect();
+    let background = bare(&opaque_hex(&terminal.background));
+
+    let mut out = format!("# {}\n\n[colors]\n", context.header());
+    let _ = writeln!(
+        out,
+        "foreground={}",
+        bare(&opaque_hex(&terminal.foreground))
+    );
+    let _ = writeln!(out, "background={}", background);
+    let _ = writeln!(
+        out,
+        "cursor={} {}",
+        background,
+        bare(&opaque_hex(&terminal.cursor))
+    );
+    for (index, color) in palette[..8].iter().enumerate() {
+        let _ = writeln!(out, "regular{}={}", index, color);
+    }
+    for (index, color) in palette[8..].iter().enumerate() {
+        let _ = writeln!(out, "bright{}={}", index, color);
+    }
+    out
+}
+
+fn toml_array(colors: &[String]) -> String {
+    let quoted: Vec<String> = colors
+        .iter()
+        .map(|color| format!("\"{}\"", color))
+        .collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+pub(super) fn wezterm(context: &ExportContext<'_>) -> String {
+    let terminal = terminal(context);
+    let palette = palette(terminal);
+    let cursor = opaque_hex(&terminal.cursor);
+
+    let mut out = format!("# {}\n\n[metadata]\n", context.header());
+    let _ = writeln!(out, "name = {}\n", serde_json::Value::from(context.title));
+    let _ = writeln!(out, "[colors]");
+    let _ = writeln!(out, "foreground = \"{}\"", opaque_hex(&terminal.foreground));
+    let _ = writeln!(out, "background = \"{}\"", opaque_hex(&terminal.background));
+    let _ = writeln!(out, "cursor_bg = \"{}\"", cursor);
+    let _ = writeln!(out, "cursor_border = \"{}\"", cursor);
+    let _ = writeln!(out, "cursor_fg = \"{}\"", opaque_hex(&terminal.background));
+    let _ = writeln!(out, "ansi = {}", toml_array(&palette[..8]));
+    let _ = writeln!(out, "brights = {}", toml_array(&palette[8..]));
+    out
+}
+
+pub(super) fn gnome_terminal(context: &ExportContext<'_>) -> String {
+    let terminal = terminal(context);
+    let rgb = |color: &crate::Color| {
+        let [red, green, blue, _] = color.to_rgba8();
+        format!("'rgb({},{},{})'", red, green, blue)
+    };
+    let palette: Vec<String> = terminal.ansi.colors().into_iter().map(rgb).collect();
+
+    let mut out = format!("# {}\n\n[/]\n", context.header());
+    let _ = writeln!(out, "use-theme-colors=false");
+    let _ = writeln!(out, "foreground-color={}", rgb(&terminal.foreground));
+    let _ = writeln!(out, "background-color={}", rgb(&terminal.background));
+    let _ = writeln!(out, "bold-color-same-as-fg=true");
+    let _ = writeln!(out, "cursor-colors-set=true");
+    let _ = writeln!(out, "cursor-background-color={}", rgb(&terminal.cursor));
+    let _ = writeln!(out, "cursor-foreground-color={}", rgb(&terminal.background));
+    let _ = writeln!(out, "palette=[{}]", palette.join(", "));
+    out
+}
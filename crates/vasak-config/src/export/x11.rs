@@ -0,0 +1,88 @@
+//! X resources and xsettingsd settings, for X11 and legacy applications.
+
+use std::fmt::Write;
+
+use super::{opaque_hex, ExportContext};
+use crate::models::SchemeVariant;
+
+/// Terminal colors as X resources, read on startup by xterm, urxvt, st and
+/// friends.
+pub(super) fn xresources(context: &ExportContext<'_>) -> String {
+    let terminal = &context.scheme.colors.variant(context.variant).terminal;
+
+    let mut out = format!("! {}\n", context.header());
+    let _ = writeln!(out, "*.foreground: {}", opaque_hex(&terminal.foreground));
+    let _ = writeln!(out, "*.background: {}", opaque_hex(&terminal.background));
+    let _ = writeln!(out, "*.cursorColor: {}", opaque_hex(&terminal.cursor));
+    for (index, color) in terminal.ansi.colors().into_iter().enumerate() {
+        let _ = writeln!(out, "*.color{}: {}", index, opaque_hex(color));
+    }
+    out
+}
+
+/// xsettingsd strings are double-quoted, with `\` as escape.
+fn xsettings_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// GNOME font size, for fonts that do not specify one.
+const DEFAULT_FONT_SIZE: u32 = 11;
+
+/// GTK reads XSETTINGS fonts as Pango descriptions, "Family Size"; without
+/// a size it would use its own, different from the gsettings one.
+fn pango_font(font: &str) -> String {
+    let has_size = font
+        .rsplit_once(' ')
+        .is_some_and(|(_, size)| size.parse::<f32>().is_ok_and(|size| size > 0.0));
+    if has_size {
+        font.to_string()
+    } else {
+        format!("{} {}", font, DEFAULT_FONT_SIZE)
+    }
+}
+
+/// GTK theme, icons and fonts as XSETTINGS. The theme is the same Adwaita set
+/// in gsettings; icons and fonts are only written with a config, and when
+/// they are not empty.
+pub(super) fn xsettingsd(context: &ExportContext<'_>) -> String {
+    let theme = match context.variant {
+        SchemeVariant::Dark => "Adwaita-dark",
+        SchemeVariant::Light => "Adwaita",
+    };
+
+    let mut out = format!("# {}\n", context.header());
+    let _ = writeln!(out, "Net/ThemeName {}", xsettings_string(theme));
+
+    let Some(config) = context.config else {
+        return out;
+    };
+    let icons = match context.variant {
+        SchemeVariant::Dark => config.icons.dark.trim(),
+        SchemeVariant::Light => config.icons.light.trim(),
+    };
+    if !icons.is_empty() {
+        let _ = writeln!(out, "Net/IconThemeName {}", xsettings_string(icons));
+    }
+    for (name, font) in [
+        ("Gtk/FontName", config.fonts.apps.trim()),
+        ("Gtk/MonospaceFontName", config.fonts.termina.trim()),
+    ] {
+        if !font.is_empty() {
+            let _ = writeln!(out, "{} {}", name, xsettings_string(&pango_font(font)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pango_font_adds_the_default_size() {
+        assert_eq!(pango_font("Inter"), "Inter 11");
+        assert_eq!(pango_font("JetBrains Mono"), "JetBrains Mono 11");
+        assert_eq!(pango_font("Inter 10"), "Inter 10");
+        assert_eq!(pango_font("Source Code Pro 10.5"), "Source Code Pro 10.5");
+    }
+}
@@ -0,0 +1,417 @@
+//! Builds a complete color scheme from a single accent color.
+//!
+//! Every role is taken from a tonal palette in OKLCH: a fixed hue and chroma
+//! whose lightness (the tone) changes per role. Text tones are then adjusted
+//! until they reach their WCAG contrast target.
+
+use serde::Deserialize;
+
+use crate::color::{Color, ColorFormat};
+use crate::contrast::contrast_ratio;
+use crate::models::{
+    AnsiColors, ColorPalette, SchemeColors, SchemeData, TerminalColors, TextColors, ThemeVariant,
+    UiColors,
+};
+
+/// Options for [`generate_scheme`]. Every field has a default derived from
+/// the accent color.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GenerateOptions {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Por debajo de esta croma el acento se trata como gris y su tono no cuenta.
+const ACHROMATIC_CHROMA: f64 = 0.02;
+
+/// Matices OKLCH de referencia para los colores ANSI.
+const ANSI_HUES: [f64; 6] = [25.0, 145.0, 95.0, 260.0, 330.0, 200.0];
+
+/// A hue and a maximum chroma; each tone is one lightness of it.
+#[derive(Debug, Clone, Copy)]
+struct TonalPalette {
+    hue: f64,
+    chroma: f64,
+}
+
+impl TonalPalette {
+    fn new(hue: f64, chroma: f64) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            chroma,
+        }
+    }
+
+    /// Color at `lightness`, lowering the chroma until it fits in sRGB.
+    fn tone(&self, lightness: f64) -> Color {
+        let lightness = lightness.clamp(0.0, 1.0);
+        let at = |chroma: f64| Color::from_oklch(lightness, chroma, self.hue, 1.0);
+
+        let mut color = at(self.chroma);
+        if !color.in_srgb_gamut() {
+            let (mut low, mut high) = (0.0, self.chroma);
+            for _ in 0..20 {
+                let mid = (low + high) / 2.0;
+                if at(mid).in_srgb_gamut() {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            color = at(low);
+        }
+
+        // Se redondea a hex aquí para que el contraste se mida sobre el
+        // color que realmente se escribe.
+        let [red, green, blue, _] = color.to_rgba8();
+        Color::from_rgba(
+            f64::from(red) / 255.0,
+            f64::from(green) / 255.0,
+            f64::from(blue) / 255.0,
+            1.0,
+        )
+    }
+
+    /// First tone from `lightness`, moving away from the first background,
+    /// whose contrast with every background reaches `target`.
+    fn tone_with_contrast(&self, lightness: f64, backgrounds: &[&Color], target: f64) -> Color {
+        let (background_lightness, _, _) = backgrounds[0].to_oklch();
+        let step = if lightness >= background_lightness {
+            0.01
+        } else {
+            -0.01
+        };
+
+        let mut lightness = lightness;
+        loop {
+            let color = self.tone(lightness);
+            let reached = backgrounds
+                .iter()
+                .all(|background| contrast_ratio(&color, background) >= target);
+            if reached || !(0.0..=1.0).contains(&(lightness + step)) {
+                return color;
+            }
+            lightness += step;
+        }
+    }
+}
+
+/// Acerca un matiz al del acento, como mucho 15 grados, para que los colores
+/// ANSI combinen con él sin perder su identidad.
+fn harmonize(hue: f64, accent_hue: f64) -> f64 {
+    let difference = (accent_hue - hue + 540.0).rem_euclid(360.0) - 180.0;
+    hue + (difference * 0.5).clamp(-15.0, 15.0)
+}
+
+struct Palettes {
+    primary: TonalPalette,
+    secondary: TonalPalette,
+    neutral: TonalPalette,
+    neutral_variant: TonalPalette,
+    /// `None` cuando el acento es gris y no hay matiz que respetar.
+    accent_hue: Option<f64>,
+}
+
+impl Palettes {
+    fn from_accent(accent: &Color) -> Self {
+        let (_, chroma, hue) = accent.to_oklch();
+        if chroma < ACHROMATIC_CHROMA {
+            return Self {
+                primary: TonalPalette::new(hue, chroma),
+                secondary: TonalPalette::new(hue, chroma),
+                neutral: TonalPalette::new(hue, 0.0),
+                neutral_variant: TonalPalette::new(hue, 0.0),
+                accent_hue: None,
+            };
+        }
+
+        let chroma = chroma.clamp(0.08, 0.2);
+        Self {
+            primary: TonalPalette::new(hue, chroma),
+            secondary: TonalPalette::new(hue + 45.0, chroma * 0.7),
+            neutral: TonalPalette::new(hue, (chroma * 0.1).min(0.015)),
+            neutral_variant: TonalPalette::new(hue, (chroma * 0.25).min(0.035)),
+            accent_hue: Some(hue),
+        }
+    }
+
+    fn ansi(&self, hue: f64, chroma: f64) -> TonalPalette {
+        let hue = match self.accent_hue {
+            Some(accent_hue) => harmonize(hue, accent_hue),
+            None => hue,
+        };
+        TonalPalette::new(hue, chroma)
+    }
+}
+
+/// Tonos de cada rol en una variante.
+struct Tones {
+    background: f64,
+    surface: f64,
+    border: f64,
+    primary: f64,
+    secondary: f64,
+    text_main: f64,
+    text_muted: f64,
+    on_primary: f64,
+    ansi: f64,
+    ansi_bright: f64,
+}
+
+const DARK_TONES: Tones = Tones {
+    background: 0.18,
+    surface: 0.22,
+    border: 0.35,
+    primary: 0.72,
+    secondary: 0.75,
+    text_main: 0.93,
+    text_muted: 0.75,
+    on_primary: 0.2,
+    ansi: 0.7,
+    ansi_bright: 0.8,
+};
+
+const LIGHT_TONES: Tones = Tones {
+    background: 0.985,
+    surface: 1.0,
+    border: 0.88,
+    primary: 0.5,
+    secondary: 0.55,
+    text_main: 0.22,
+    text_muted: 0.48,
+    on_primary: 0.99,
+    ansi: 0.5,
+    ansi_bright: 0.58,
+};
+
+/// Los 16 colores ANSI, con contraste mínimo de 3:1 sobre `background`.
+fn ansi_colors(palettes: &Palettes, tones: &Tones, background: &Color) -> AnsiColors {
+    let ansi_color = |index: usize, bright: bool| {
+        let lightness = if bright {
+            tones.ansi_bright
+        } else {
+            tones.ansi
+        };
+        palettes
+            .ansi(ANSI_HUES[index], 0.14)
+            .tone_with_contrast(lightness, &[background], 3.0)
+    };
+    AnsiColors {
+        black: palettes.neutral.tone(0.3),
+        red: ansi_color(0, false),
+        green: ansi_color(1, false),
+        yellow: ansi_color(2, false),
+        blue: ansi_color(3, false),
+        magenta: ansi_color(4, false),
+        cyan: ansi_color(5, false),
+        white: palettes.neutral.tone(0.85),
+        bright_black: palettes.neutral.tone(0.5),
+        bright_red: ansi_color(0, true),
+        bright_green: ansi_color(1, true),
+        bright_yellow: ansi_color(2, true),
+        bright_blue: ansi_color(3, true),
+        bright_magenta: ansi_color(4, true),
+        bright_cyan: ansi_color(5, true),
+        bright_white: palettes.neutral.tone(0.97),
+    }
+}
+
+/// Terminal colors for a variant that only defines `ui`: its text and
+/// background, the primary color as cursor and ANSI colors tuned to its
+/// primary hue. Dark or light tones follow the lightness of the background.
+pub fn derive_terminal(ui: &UiColors) -> TerminalColors {
+    let palettes = Palettes::from_accent(&ui.color.primary);
+    let (background_lightness, _, _) = ui.background.to_oklch();
+    let tones = if background_lightness < 0.5 {
+        &DARK_TONES
+    } else {
+        &LIGHT_TONES
+    };
+
+    TerminalColors {
+        foreground: ui.text.main.clone(),
+        background: ui.background.clone(),
+        cursor: ui.color.primary.clone(),
+        ansi: ansi_colors(&palettes, tones, &ui.background),
+    }
+}
+
+fn build_variant(palettes: &Palettes, tones: &Tones) -> ThemeVariant {
+    let background = palettes.neutral.tone(tones.background);
+    let surface = palettes.neutral.tone(tones.surface);
+    let backgrounds = [&background, &surface];
+
+    let primary = palettes
+        .primary
+        .tone_with_contrast(tones.primary, &backgrounds, 3.0);
+    let secondary = palettes
+        .secondary
+        .tone_with_contrast(tones.secondary, &backgrounds, 3.0);
+    let text = TextColors {
+        main: palettes
+            .neutral
+            .tone_with_contrast(tones.text_main, &backgrounds, 7.0),
+        muted: palettes
+            .neutral_variant
+            .tone_with_contrast(tones.text_muted, &backgrounds, 4.5),
+        on_primary: palettes
+            .primary
+            .tone_with_contrast(tones.on_primary, &[&primary], 4.5),
+    };
+
+    ThemeVariant {
+        terminal: TerminalColors {
+            foreground: text.main.clone(),
+            background: background.clone(),
+            cursor: primary.clone(),
+            ansi: ansi_colors(palettes, tones, &background),
+        },
+        derived: Vec::new(),
+        ui: UiColors {
+            color: ColorPalette { primary, secondary },
+            text,
+            border: palettes.neutral_variant.tone(tones.border),
+            background,
+            surface,
+        },
+    }
+}
+
+/// Builds a scheme with both variants from `accent`.
+///
+/// Main text reaches WCAG AAA (7:1) and muted and on-primary text reach AA
+/// (4.5:1) on their backgrounds; the ANSI colors keep their usual hues,
+/// nudged towards the accent.
+pub fn generate_scheme(accent: &Color, options: &GenerateOptions) -> SchemeData {
+    let palettes = Palettes::from_accent(accent);
+    let hex = accent.format(ColorFormat::Hex);
+    let hex_digits = hex.trim_start_matches('#');
+
+    SchemeData {
+        id: options
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("generated-{}", hex_digits)),
+        name: options
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Generated {}", hex)),
+        author: options.author.clone().unwrap_or_default(),
+        description: options
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Generated from the accent color {}", hex)),
+        version: "1.0.0".to_string(),
+        extends: None,
+        colors: SchemeColors {
+            dark: build_variant(&palettes, &DARK_TONES),
+            light: build_variant(&palettes, &LIGHT_TONES),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contrast::{check_scheme_contrast, contrast_ratio, ContrastOptions};
+
+    const ACCENTS: [&str; 8] = [
+        "#3584e4",
+        "#808080",
+        "#000000",
+        "#ffffff",
+        "#ffff00",
+        "#e01b24",
+        // Fuera de sRGB: la croma se recorta en cada tono.
+        "oklch(70% 0.4 145)",
+        "oklch(60% 0.37 300)",
+    ];
+
+    fn generate(accent: &str) -> SchemeData {
+        generate_scheme(&Color::parse(accent).unwrap(), &GenerateOptions::default())
+    }
+
+    #[test]
+    fn every_accent_passes_aa() {
+        for accent in ACCENTS {
+            let scheme = generate(accent);
+            let report = check_scheme_contrast(&scheme, ContrastOptions::default());
+            let failing: Vec<_> = report
+                .pairs
+                .iter()
+                .filter(|pair| !pair.wcag.aa)
+                .map(|pair| (pair.variant, &pair.foreground_role, pair.ratio))
+                .collect();
+            assert!(report.passes_aa, "{}: {:?}", accent, failing);
+        }
+    }
+
+    #[test]
+    fn main_text_reaches_aaa() {
+        for accent in ACCENTS {
+            let scheme = generate(accent);
+            for variant in [&scheme.colors.dark, &scheme.colors.light] {
+                for background in [&variant.ui.background, &variant.ui.surface] {
+                    let ratio = contrast_ratio(&variant.ui.text.main, background);
+                    assert!(ratio >= 7.0, "{}: {}", accent, ratio);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ansi_colors_are_readable_on_the_background() {
+        for accent in ACCENTS {
+            let scheme = generate(accent);
+            for variant in [&scheme.colors.dark, &scheme.colors.light] {
+                let terminal = &variant.terminal;
+                for color in [
+                    &terminal.ansi.red,
+                    &terminal.ansi.blue,
+                    &terminal.ansi.bright_cyan,
+                ] {
+                    let ratio = contrast_ratio(color, &terminal.background);
+                    assert!(ratio >= 3.0, "{}: {} {}", accent, color, ratio);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_colors_are_plain_hex_in_gamut() {
+        let scheme = generate("oklch(70% 0.4 145)");
+        let ui = &scheme.colors.dark.ui;
+        for color in [&ui.color.primary, &ui.background, &ui.text.main] {
+            assert!(color.in_srgb_gamut());
+            assert_eq!(color.as_str(), color.format(ColorFormat::Hex));
+        }
+    }
+
+    #[test]
+    fn defaults_come_from_the_accent() {
+        let scheme = generate("#3584E4");
+        assert_eq!(scheme.id, "generated-3584e4");
+        assert_eq!(scheme.name, "Generated #3584e4");
+        assert!(scheme.extends.is_none());
+
+        let named = generate_scheme(
+            &Color::parse("#3584e4").unwrap(),
+            &GenerateOptions {
+                id: Some("ocean".to_string()),
+                name: Some("Ocean".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!((named.id.as_str(), named.name.as_str()), ("ocean", "Ocean"));
+    }
+
+    #[test]
+    fn harmonize_moves_at_most_fifteen_degrees() {
+        assert_eq!(harmonize(25.0, 35.0), 30.0);
+        assert_eq!(harmonize(25.0, 195.0), 40.0);
+        assert_eq!(harmonize(350.0, 10.0), 360.0);
+    }
+}
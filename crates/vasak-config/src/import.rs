@@ -0,0 +1,539 @@
+//! Imports community color schemes: base16 and base24 YAML, and iTerm2
+//! `.itermcolors` presets.
+//!
+//! These formats have a single palette, so both variants of the imported
+//! scheme are the same. Slots map to roles as follows:
+//!
+//! | Role | base16 / base24 | iTerm2 |
+//! |---|---|---|
+//! | `ui.background`, `terminal.background`, `ui.text.on-primary` | `base00` | Background |
+//! | `ui.surface` | `base01` | 6% of Foreground over Background |
+//! | `ui.border` | `base02` | Selection |
+//! | `ui.text.main`, `terminal.foreground` | `base05` | Foreground |
+//! | `ui.text.muted` | `base04` | 65% of Foreground over Background |
+//! | `ui.color.primary` | `base0D` | Ansi 4 |
+//! | `ui.color.secondary` | `base0E` | Ansi 5 |
+//! | `terminal.cursor` | `base05` | Cursor |
+//! | ANSI black, red, green, yellow, blue, magenta, cyan, white | `base00`, `08`, `0B`, `0A`, `0D`, `0E`, `0C`, `05` | Ansi 0 to 7 |
+//! | ANSI bright black, red, green, yellow, blue, magenta, cyan, white | `base03`, `12`, `14`, `13`, `16`, `17`, `15`, `07` | Ansi 8 to 15 |
+//!
+//! base16 schemes, which lack `base12`..`base17`, use the normal colors for
+//! the bright ones.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::export::mix;
+use crate::models::{
+    AnsiColors, ColorPalette, SchemeColors, SchemeData, TerminalColors, TextColors, ThemeVariant,
+    UiColors,
+};
+
+/// A scheme format the importer understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// base16 or base24 YAML, in the current (`palette:`) or the legacy
+    /// (top-level `baseXX`) layout.
+    Base16,
+    /// iTerm2 color preset (XML plist).
+    Itermcolors,
+}
+
+impl ImportFormat {
+    /// Format of a file, from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "yaml" | "yml" => Some(ImportFormat::Base16),
+            "itermcolors" => Some(ImportFormat::Itermcolors),
+            _ => None,
+        }
+    }
+}
+
+/// Id of the scheme imported from `path`: its file name without extension,
+/// lowercased, with characters not allowed in ids replaced by `-`.
+pub fn scheme_id_for(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '-' | '_' | '.') => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// The 24 base24 slots; base16 uses the first 16.
+struct Base16Palette {
+    slots: HashMap<String, Color>,
+}
+
+impl Base16Palette {
+    fn slot(&self, name: &str) -> Result<Color, String> {
+        self.slots
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("missing color {}", name))
+    }
+
+    /// A base24 slot, falling back to a base16 one.
+    fn slot_or(&self, name: &str, fallback: &str) -> Result<Color, String> {
+        match self.slots.get(name) {
+            Some(color) => Ok(color.clone()),
+            None => self.slot(fallback),
+        }
+    }
+}
+
+/// Old files often leave hex values unquoted, and YAML reads `181818` or
+/// `123e45` as numbers; the original text is lost by then, so the value is
+/// rejected instead of guessed.
+fn yaml_color(key: &str, value: &serde_yaml_ng::Value) -> Result<String, String> {
+    match value {
+        serde_yaml_ng::Value::String(text) => Ok(text.clone()),
+        serde_yaml_ng::Value::Number(_) => Err(format!(
+            "{} must be a quoted string: YAML reads the unquoted hex as a number",
+            key
+        )),
+        _ => Err(format!("{} is not a color", key)),
+    }
+}
+
+fn yaml_text(document: &serde_yaml_ng::Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| document.get(*key)?.as_str())
+        .map(str::to_string)
+}
+
+fn parse_base16(content: &str) -> Result<(Base16Palette, Metadata), String> {
+    let document: serde_yaml_ng::Value =
+        serde_yaml_ng::from_str(content).map_err(|e| e.to_string())?;
+    let slots_source = match document.get("palette") {
+        Some(palette) if palette.is_mapping() => palette,
+        _ => &document,
+    };
+    let mapping = slots_source
+        .as_mapping()
+        .ok_or_else(|| "expected a YAML mapping".to_string())?;
+
+    let mut slots = HashMap::new();
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        let is_slot = key.len() == 6
+            && key.starts_with("base")
+            && key[4..].chars().all(|c| c.is_ascii_hexdigit());
+        if !is_slot {
+            continue;
+        }
+        let text = yaml_color(key, value)?;
+        let hex = format!("#{}", text.trim().trim_start_matches('#'));
+        let color = Color::parse(&hex).map_err(|e| format!("{}: {}", key, e))?;
+        // `base0d` and `base0D` are the same slot.
+        slots.insert(format!("base{}", key[4..].to_ascii_uppercase()), color);
+    }
+
+    let metadata = Metadata {
+        name: yaml_text(&document, &["name", "scheme"]),
+        author: yaml_text(&document, &["author"]),
+        description: yaml_text(&document, &["description"]),
+    };
+    Ok((Base16Palette { slots }, metadata))
+}
+
+fn base16_variant(palette: &Base16Palette) -> Result<ThemeVariant, String> {
+    let slot = |name: &str| palette.slot(name);
+    let background = slot("base00")?;
+    let foreground = slot("base05")?;
+
+    Ok(ThemeVariant {
+        ui: UiColors {
+            color: ColorPalette {
+                primary: slot("base0D")?,
+                secondary: slot("base0E")?,
+            },
+            text: TextColors {
+                main: foreground.clone(),
+                muted: slot("base04")?,
+                on_primary: background.clone(),
+            },
+            background: background.clone(),
+            border: slot("base02")?,
+            surface: slot("base01")?,
+        },
+        terminal: TerminalColors {
+            foreground: foreground.clone(),
+            background: background.clone(),
+            cursor: foreground.clone(),
+            ansi: AnsiColors {
+                black: background,
+                red: slot("base08")?,
+                green: slot("base0B")?,
+                yellow: slot("base0A")?,
+                blue: slot("base0D")?,
+                magenta: slot("base0E")?,
+                cyan: slot("base0C")?,
+                white: foreground,
+                bright_black: slot("base03")?,
+                bright_red: palette.slot_or("base12", "base08")?,
+                bright_green: palette.slot_or("base14", "base0B")?,
+                bright_yellow: palette.slot_or("base13", "base0A")?,
+                bright_blue: palette.slot_or("base16", "base0D")?,
+                bright_magenta: palette.slot_or("base17", "base0E")?,
+                bright_cyan: palette.slot_or("base15", "base0C")?,
+                bright_white: slot("base07")?,
+            },
+        },
+        derived: Vec::new(),
+    })
+}
+
+/// `Red Component`, `Green Component`... of an iTerm2 color, in sRGB.
+fn iterm_color(preset: &plist::Dictionary, key: &str) -> Result<Option<Color>, String> {
+    let Some(value) = preset.get(key) else {
+        return Ok(None);
+    };
+    let color = value
+        .as_dictionary()
+        .ok_or_else(|| format!("{} is not a color", key))?;
+    let component = |name: &str| {
+        color
+            .get(&format!("{} Component", name))
+            .and_then(plist::Value::as_real)
+    };
+    match (component("Red"), component("Green"), component("Blue")) {
+        (Some(red), Some(green), Some(blue)) => Ok(Some(Color::from_rgba(
+            red,
+            green,
+            blue,
+            component("Alpha").unwrap_or(1.0),
+        ))),
+        _ => Err(format!("{} lacks a color component", key)),
+    }
+}
+
+fn itermcolors_variant(content: &str) -> Result<ThemeVariant, String> {
+    let value = plist::Value::from_reader_xml(content.as_bytes()).map_err(|e| e.to_string())?;
+    let preset = value
+        .as_dictionary()
+        .ok_or_else(|| "expected a plist dictionary".to_string())?;
+    let required = |key: &str| -> Result<Color, String> {
+        iterm_color(preset, key)?.ok_or_else(|| format!("missing {}", key))
+    };
+
+    let ansi: Vec<Color> = (0..16)
+        .map(|index| required(&format!("Ansi {} Color", index)))
+        .collect::<Result<_, _>>()?;
+    let [
+        black,
+        red,
+        green,
+        yellow,
+        blue,
+        magenta,
+        cyan,
+        white,
+        bright_black,
+        bright_red,
+        bright_green,
+        bright_yellow,
+        bright_blue,
+        bright_magenta,
+        bright_cyan,
+        bright_white,
+    ]: [Color; 16] = ansi.try_into().expect("16 ANSI colors");
+    let background = required("Background Color")?;
+    let foreground = required("Foreground Color")?;
+    let cursor = iterm_color(preset, "Cursor Color")?.unwrap_or_else(|| foreground.clone());
+    let border = iterm_color(preset, "Selection Color")?
+        .unwrap_or_else(|| mix(&background, &foreground, 0.2));
+
+    let ansi = AnsiColors {
+        black,
+        red,
+        green,
+        yellow,
+        blue,
+        magenta,
+        cyan,
+        white,
+        bright_black,
+        bright_red,
+        bright_green,
+        bright_yellow,
+        bright_blue,
+        bright_magenta,
+        bright_cyan,
+        bright_white,
+    };
+
+    Ok(ThemeVariant {
+        ui: UiColors {
+            color: ColorPalette {
+                primary: ansi.blue.clone(),
+                secondary: ansi.magenta.clone(),
+            },
+            text: TextColors {
+                main: foreground.clone(),
+                muted: mix(&background, &foreground, 0.65),
+                on_primary: background.clone(),
+            },
+            surface: mix(&background, &foreground, 0.06),
+            background: background.clone(),
+            border,
+        },
+        terminal: TerminalColors {
+            foreground,
+            background,
+            cursor,
+            ansi,
+        },
+        derived: Vec::new(),
+    })
+}
+
+#[derive(Default)]
+struct Metadata {
+    name: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+}
+
+/// Converts the content; errors come without a prefix, for the registry's
+/// diagnostics.
+pub(crate) fn convert(content: &str, format: ImportFormat, id: &str) -> Result<SchemeData, String> {
+    let (variant, metadata, origin) = match format {
+        ImportFormat::Base16 => {
+            let (palette, metadata) = parse_base16(content)?;
+            let origin = if palette.slots.contains_key("base12") {
+                "base24"
+            } else {
+                "base16"
+            };
+            (base16_variant(&palette)?, metadata, origin)
+        }
+        ImportFormat::Itermcolors => (itermcolors_variant(content)?, Metadata::default(), "iTerm2"),
+    };
+
+    Ok(SchemeData {
+        id: id.to_string(),
+        name: metadata.name.unwrap_or_else(|| id.to_string()),
+        author: metadata.author.unwrap_or_default(),
+        description: metadata
+            .description
+            .unwrap_or_else(|| format!("Imported from a {} scheme", origin)),
+        version: "1.0.0".to_string(),
+        extends: None,
+        colors: SchemeColors {
+            dark: variant.clone(),
+            light: variant,
+        },
+    })
+}
+
+/// Converts a base16/base24 or iTerm2 scheme into a Vasak scheme with `id`.
+pub fn import_scheme(content: &str, format: ImportFormat, id: &str) -> crate::Result<SchemeData> {
+    convert(content, format, id).map_err(|e| crate::Error::Other(format!("Invalid scheme: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE16: &str = r##"
+system: "base16"
+name: "Test Dark"
+author: "Vasak"
+variant: "dark"
+palette:
+  base00: "#181818"
+  base01: "#282828"
+  base02: "#383838"
+  base03: "#585858"
+  base04: "#b8b8b8"
+  base05: "#d8d8d8"
+  base06: "#e8e8e8"
+  base07: "#f8f8f8"
+  base08: "#ab4642"
+  base09: "#dc9656"
+  base0A: "#f7ca88"
+  base0B: "#a1b56c"
+  base0C: "#86c1b9"
+  base0D: "#7cafc2"
+  base0E: "#ba8baf"
+  base0F: "#a16946"
+"##;
+
+    const BASE24_EXTRA: &str = r##"
+  base10: "#101010"
+  base11: "#080808"
+  base12: "#ff5555"
+  base13: "#ffff55"
+  base14: "#55ff55"
+  base15: "#55ffff"
+  base16: "#5555ff"
+  base17: "#ff55ff"
+"##;
+
+    fn iterm_color(red: f64, green: f64, blue: f64) -> String {
+        format!(
+            "<dict><key>Red Component</key><real>{}</real>\
+             <key>Green Component</key><real>{}</real>\
+             <key>Blue Component</key><real>{}</real></dict>",
+            red, green, blue
+        )
+    }
+
+    fn itermcolors(with_cursor: bool) -> String {
+        let mut entries = String::new();
+        for index in 0..16 {
+            let level = f64::from(index) / 15.0;
+            entries.push_str(&format!(
+                "<key>Ansi {} Color</key>{}",
+                index,
+                iterm_color(level, 0.0, 1.0 - level)
+            ));
+        }
+        entries.push_str(&format!(
+            "<key>Background Color</key>{}",
+            iterm_color(0.0, 0.0, 0.0)
+        ));
+        entries.push_str(&format!(
+            "<key>Foreground Color</key>{}",
+            iterm_color(1.0, 1.0, 1.0)
+        ));
+        if with_cursor {
+            entries.push_str(&format!(
+                "<key>Cursor Color</key>{}",
+                iterm_color(1.0, 0.0, 0.0)
+            ));
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <plist version=\"1.0\"><dict>{}</dict></plist>",
+            entries
+        )
+    }
+
+    #[test]
+    fn base16_slots_map_to_roles() {
+        let scheme = convert(BASE16, ImportFormat::Base16, "test-dark").unwrap();
+        assert_eq!(scheme.id, "test-dark");
+        assert_eq!(scheme.name, "Test Dark");
+        assert_eq!(scheme.author, "Vasak");
+        assert_eq!(scheme.description, "Imported from a base16 scheme");
+        assert_eq!(scheme.colors.dark, scheme.colors.light);
+
+        let variant = &scheme.colors.dark;
+        assert_eq!(variant.ui.background.as_str(), "#181818");
+        assert_eq!(variant.ui.surface.as_str(), "#282828");
+        assert_eq!(variant.ui.color.primary.as_str(), "#7cafc2");
+        assert_eq!(variant.ui.text.muted.as_str(), "#b8b8b8");
+        assert_eq!(variant.terminal.ansi.red.as_str(), "#ab4642");
+        assert_eq!(variant.terminal.ansi.bright_black.as_str(), "#585858");
+        // Without base12..base17, the bright colors repeat the normal ones.
+        assert_eq!(variant.terminal.ansi.bright_red.as_str(), "#ab4642");
+        assert_eq!(variant.terminal.ansi.bright_blue.as_str(), "#7cafc2");
+    }
+
+    #[test]
+    fn base24_adds_the_bright_colors() {
+        let content = format!("{}{}", BASE16.trim_end(), BASE24_EXTRA);
+        let scheme = convert(&content, ImportFormat::Base16, "test").unwrap();
+        let ansi = &scheme.colors.dark.terminal.ansi;
+        assert_eq!(scheme.description, "Imported from a base24 scheme");
+        assert_eq!(ansi.bright_red.as_str(), "#ff5555");
+        assert_eq!(ansi.bright_yellow.as_str(), "#ffff55");
+        assert_eq!(ansi.bright_green.as_str(), "#55ff55");
+        assert_eq!(ansi.bright_cyan.as_str(), "#55ffff");
+        assert_eq!(ansi.bright_blue.as_str(), "#5555ff");
+        assert_eq!(ansi.bright_magenta.as_str(), "#ff55ff");
+    }
+
+    #[test]
+    fn legacy_layout_without_hash_or_palette() {
+        let content: String = BASE16
+            .replace("palette:\n", "")
+            .replace("  base", "base")
+            .replace("\"#", "\"")
+            .replace("base0D", "base0d")
+            .replace("name: \"Test Dark\"", "scheme: \"Legacy\"");
+        let scheme = convert(&content, ImportFormat::Base16, "legacy").unwrap();
+        assert_eq!(scheme.name, "Legacy");
+        assert_eq!(scheme.colors.dark.ui.color.primary.as_str(), "#7cafc2");
+    }
+
+    #[test]
+    fn unquoted_numeric_colors_are_rejected() {
+        for value in ["181818", "0x1818", "123e45"] {
+            let content = BASE16.replace("\"#181818\"", value);
+            let error = convert(&content, ImportFormat::Base16, "test").unwrap_err();
+            assert!(
+                error.contains("base00 must be a quoted string"),
+                "{}: {}",
+                value,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn unquoted_colors_that_stay_strings_are_accepted() {
+        let content = BASE16.replace("\"#181818\"", "1e1e1e");
+        let scheme = convert(&content, ImportFormat::Base16, "test").unwrap();
+        assert_eq!(scheme.colors.dark.ui.background.as_str(), "#1e1e1e");
+    }
+
+    #[test]
+    fn base16_reports_missing_slots() {
+        let content = BASE16.replace("  base0D: \"#7cafc2\"\n", "");
+        let error = convert(&content, ImportFormat::Base16, "test").unwrap_err();
+        assert_eq!(error, "missing color base0D");
+    }
+
+    #[test]
+    fn itermcolors_maps_ansi_and_ui_roles() {
+        let scheme = convert(&itermcolors(true), ImportFormat::Itermcolors, "iterm").unwrap();
+        let variant = &scheme.colors.dark;
+        assert_eq!(scheme.name, "iterm");
+        assert_eq!(scheme.description, "Imported from a iTerm2 scheme");
+        assert_eq!(variant.terminal.ansi.black.as_str(), "#0000ff");
+        assert_eq!(variant.terminal.ansi.bright_white.as_str(), "#ff0000");
+        assert_eq!(variant.terminal.cursor.as_str(), "#ff0000");
+        assert_eq!(variant.ui.background.as_str(), "#000000");
+        assert_eq!(variant.ui.text.main.as_str(), "#ffffff");
+        assert_eq!(variant.ui.color.primary, variant.terminal.ansi.blue);
+        assert_eq!(variant.ui.color.secondary, variant.terminal.ansi.magenta);
+        assert_eq!(variant.ui.text.muted.as_str(), "#a6a6a6");
+    }
+
+    #[test]
+    fn itermcolors_cursor_defaults_to_the_foreground() {
+        let scheme = convert(&itermcolors(false), ImportFormat::Itermcolors, "iterm").unwrap();
+        assert_eq!(scheme.colors.dark.terminal.cursor.as_str(), "#ffffff");
+
+        let broken = itermcolors(false).replace("<key>Ansi 3 Color</key>", "<key>Other</key>");
+        let error = convert(&broken, ImportFormat::Itermcolors, "iterm").unwrap_err();
+        assert_eq!(error, "missing Ansi 3 Color");
+    }
+
+    #[test]
+    fn ids_come_from_the_file_name() {
+        assert_eq!(
+            scheme_id_for(Path::new("/x/Gruvbox Dark.yaml")),
+            "gruvbox-dark"
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("a.itermcolors")),
+            Some(ImportFormat::Itermcolors)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("a.yml")),
+            Some(ImportFormat::Base16)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("a.json")), None);
+    }
+}
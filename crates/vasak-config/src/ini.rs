@@ -0,0 +1,174 @@
+//! Minimal INI editing for the settings files of other applications.
+//!
+//! Only the keys being set are touched; comments, order and every other
+//! group and key are kept as they are.
+
+/// One `key=value` inside `[group]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IniEntry {
+    pub group: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl IniEntry {
+    pub fn new(group: &str, key: &str, value: &str) -> Self {
+        Self {
+            group: group.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+fn group_header(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+}
+
+fn is_key(line: &str, key: &str) -> bool {
+    line.split_once('=')
+        .is_some_and(|(name, _)| name.trim() == key)
+}
+
+/// Entries of `content`, in order; comments and lines without `=` are skipped.
+pub(crate) fn parse(content: &str) -> Vec<IniEntry> {
+    let mut group = "";
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = group_header(line) {
+            group = name;
+        } else if let Some((key, value)) = line.split_once('=') {
+            entries.push(IniEntry::new(group, key.trim(), value.trim()));
+        }
+    }
+    entries
+}
+
+/// `existing` with every entry set: replaced where the key exists, added at
+/// the end of its group otherwise, and the group appended if missing.
+pub(crate) fn set_entries(existing: &str, entries: &[IniEntry]) -> String {
+    let mut lines: Vec<String> = existing.lines().map(String::from).collect();
+
+    for entry in entries {
+        let line = format!("{}={}", entry.key, entry.value);
+        let Some(start) = lines
+            .iter()
+            .position(|line| group_header(line) == Some(entry.group.as_str()))
+        else {
+            if lines.last().is_some_and(|last| !last.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", entry.group));
+            lines.push(line);
+            continue;
+        };
+
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| group_header(line).is_some())
+            .map_or(lines.len(), |offset| start + 1 + offset);
+        match (start + 1..end).find(|&index| is_key(&lines[index], &entry.key)) {
+            Some(index) => lines[index] = line,
+            None => {
+                // Antes de las líneas en blanco que separan del grupo siguiente.
+                let mut at = end;
+                while at > start + 1 && lines[at - 1].trim().is_empty() {
+                    at -= 1;
+                }
+                lines.insert(at, line);
+            }
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QT5CT: &str = "\
+# qt5ct settings
+[Appearance]
+color_scheme_path=/usr/share/qt5ct/colors/darker.conf
+custom_palette=false
+icon_theme=breeze
+
+[Fonts]
+fixed=\"Monospace,10\"
+";
+
+    #[test]
+    fn set_entries_replaces_existing_keys_in_place() {
+        let out = set_entries(
+            QT5CT,
+            &[IniEntry::new("Appearance", "custom_palette", "true")],
+        );
+        assert_eq!(
+            out,
+            QT5CT.replace("custom_palette=false", "custom_palette=true")
+        );
+    }
+
+    #[test]
+    fn set_entries_keeps_comments_and_other_groups() {
+        let out = set_entries(
+            QT5CT,
+            &[
+                IniEntry::new("Appearance", "style", "Fusion"),
+                IniEntry::new("Interface", "menus_have_icons", "true"),
+            ],
+        );
+        assert_eq!(
+            out,
+            "\
+# qt5ct settings
+[Appearance]
+color_scheme_path=/usr/share/qt5ct/colors/darker.conf
+custom_palette=false
+icon_theme=breeze
+style=Fusion
+
+[Fonts]
+fixed=\"Monospace,10\"
+
+[Interface]
+menus_have_icons=true
+"
+        );
+    }
+
+    #[test]
+    fn set_entries_matches_keys_with_spaces_around_the_equals() {
+        let out = set_entries(
+            "[General]\nColorScheme = BreezeDark\n",
+            &[IniEntry::new("General", "ColorScheme", "Vasak")],
+        );
+        assert_eq!(out, "[General]\nColorScheme=Vasak\n");
+    }
+
+    #[test]
+    fn set_entries_creates_a_missing_file() {
+        let out = set_entries("", &[IniEntry::new("General", "ColorScheme", "Vasak")]);
+        assert_eq!(out, "[General]\nColorScheme=Vasak\n");
+    }
+
+    #[test]
+    fn parse_skips_comments_and_keeps_groups() {
+        assert_eq!(
+            parse("; note\n[Colors:View]\nBackgroundNormal=1,2,3\n# other\n[WM]\nactiveBackground=4,5,6\n"),
+            vec![
+                IniEntry::new("Colors:View", "BackgroundNormal", "1,2,3"),
+                IniEntry::new("WM", "activeBackground", "4,5,6"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Runtime-agnostic access to the Vasak desktop configuration.
+//!
+//! This crate holds the logic shared by the Tauri plugin and the non-Tauri
+//! Vasak services: config path resolution, atomic writes, defaults, color
+//! scheme loading and the optional GNOME theme sync.
+
+pub mod color;
+pub mod contrast;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+mod error;
+pub mod export;
+pub mod generate;
+#[cfg(feature = "scheme-import")]
+pub mod import;
+mod ini;
+pub mod models;
+mod schemes;
+mod store;
+mod themes;
+pub mod tokens;
+mod user_schemes;
+#[cfg(feature = "wallpaper")]
+pub mod wallpaper;
+mod watcher;
+
+pub use color::{Color, ColorFormat, ColorParseError};
+pub use contrast::{ContrastOptions, ContrastPair, ContrastReport, WcagVerdict};
+pub use error::{Error, Result};
+pub use export::ExportFormat;
+pub use generate::{generate_scheme, GenerateOptions};
+#[cfg(feature = "scheme-import")]
+pub use import::{import_scheme, ImportFormat};
+pub use models::*;
+pub use schemes::{
+    RawScheme, SchemeChanges, SchemeDiagnostic, SchemeRegistry, SchemeStatus, SchemeWatcher,
+    ShadowedScheme,
+};
+pub use store::ConfigStore;
+pub use themes::AppliedTheme;
+pub use tokens::TokenFormat;
+#[cfg(feature = "wallpaper")]
+pub use wallpaper::{WallpaperSchemeOptions, DYNAMIC_SCHEME_ID};
+pub use watcher::ConfigWatcher;
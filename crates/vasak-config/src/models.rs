@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+
+use crate::color::{Color, ColorFormat};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VSKConfig {
+    pub style: Style,
+    pub desktop: Option<Desktop>,
+    #[serde(default)]
+    pub fonts: Fonts,
+    #[serde(default)]
+    pub icons: Icons,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct Fonts {
+    pub termina: String,
+    pub title: String,
+    pub apps: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct Icons {
+    pub dark: String,
+    #[serde(default, alias = "light")]
+    pub light: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Desktop {
+    pub wallpaper: Vec<String>,
+    pub iconsize: u32,
+    pub showfiles: bool,
+    pub showhiddenfiles: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Style {
+    pub darkmode: bool,
+    #[serde(rename = "color-scheme")]
+    pub color_scheme: String,
+    pub radius: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Scheme {
+    pub path: String,
+    pub scheme: SchemeData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SchemeData {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub version: String,
+    /// Id of the scheme this one was derived from; its file only overrides
+    /// part of the parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    pub colors: SchemeColors,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SchemeColors {
+    pub dark: ThemeVariant,
+    pub light: ThemeVariant,
+}
+
+/// One of the two variants every scheme defines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemeVariant {
+    Dark,
+    Light,
+}
+
+impl SchemeVariant {
+    pub const ALL: [SchemeVariant; 2] = [SchemeVariant::Dark, SchemeVariant::Light];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SchemeVariant::Dark => "dark",
+            SchemeVariant::Light => "light",
+        }
+    }
+}
+
+impl SchemeColors {
+    pub fn variant(&self, variant: SchemeVariant) -> &ThemeVariant {
+        match variant {
+            SchemeVariant::Dark => &self.dark,
+            SchemeVariant::Light => &self.light,
+        }
+    }
+}
+
+/// A variant of a scheme. Scheme files may omit `terminal`, or any of its
+/// entries: they are derived from `ui` when the scheme is loaded.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "ThemeVariantSource")]
+pub struct ThemeVariant {
+    pub ui: UiColors,
+    pub terminal: TerminalColors,
+    /// Terminal entries derived from `ui` instead of read from the file, as
+    /// `terminal.foreground` or `terminal.ansi.brightRed`. Entries listed here
+    /// are derived again on load, so they follow later changes to `ui`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived: Vec<String>,
+}
+
+/// `ThemeVariant` tal como puede venir en el archivo.
+#[derive(Deserialize)]
+struct ThemeVariantSource {
+    ui: UiColors,
+    #[serde(default)]
+    terminal: TerminalSource,
+    #[serde(default)]
+    derived: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct TerminalSource {
+    foreground: Option<Color>,
+    background: Option<Color>,
+    cursor: Option<Color>,
+    #[serde(default)]
+    ansi: AnsiSource,
+}
+
+/// `terminal.ansi` del archivo: cualquier entrada puede faltar, pero un nombre
+/// mal escrito es un error en vez de una entrada derivada sin aviso.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct AnsiSource {
+    black: Option<Color>,
+    red: Option<Color>,
+    green: Option<Color>,
+    yellow: Option<Color>,
+    blue: Option<Color>,
+    magenta: Option<Color>,
+    cyan: Option<Color>,
+    white: Option<Color>,
+    bright_black: Option<Color>,
+    bright_red: Option<Color>,
+    bright_green: Option<Color>,
+    bright_yellow: Option<Color>,
+    bright_blue: Option<Color>,
+    bright_magenta: Option<Color>,
+    bright_cyan: Option<Color>,
+    bright_white: Option<Color>,
+}
+
+impl AnsiSource {
+    /// Entradas en el orden de [`AnsiColors::NAMES`].
+    fn into_values(self) -> [Option<Color>; 16] {
+        [
+            self.black,
+            self.red,
+            self.green,
+            self.yellow,
+            self.blue,
+            self.magenta,
+            self.cyan,
+            self.white,
+            self.bright_black,
+            self.bright_red,
+            self.bright_green,
+            self.bright_yellow,
+            self.bright_blue,
+            self.bright_magenta,
+            self.bright_cyan,
+            self.bright_white,
+        ]
+    }
+}
+
+impl From<ThemeVariantSource> for ThemeVariant {
+    fn from(source: ThemeVariantSource) -> Self {
+        let ThemeVariantSource {
+            ui,
+            terminal: given,
+            derived: previously_derived,
+        } = source;
+
+        let keys = TerminalColors::KEYS;
+        let mut values: Vec<Option<Color>> = vec![given.foreground, given.background, given.cursor];
+        values.extend(given.ansi.into_values());
+        // Lo que se marcó como derivado se vuelve a calcular desde `ui`.
+        for (value, key) in values.iter_mut().zip(keys) {
+            if previously_derived.iter().any(|derived| derived == key) {
+                *value = None;
+            }
+        }
+
+        let mut terminal = if values.iter().all(Option::is_some) {
+            TerminalColors::filled(&ui.background)
+        } else {
+            crate::generate::derive_terminal(&ui)
+        };
+        let mut derived = Vec::new();
+        for ((slot, value), key) in terminal.colors_mut().into_iter().zip(values).zip(keys) {
+            match value {
+                Some(value) => *slot = value,
+                None => derived.push(key.to_string()),
+            }
+        }
+
+        Self {
+            ui,
+            terminal,
+            derived,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UiColors {
+    pub color: ColorPalette,
+    pub text: TextColors,
+    pub background: Color,
+    pub border: Color,
+    pub surface: Color,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColorPalette {
+    pub primary: Color,
+    pub secondary: Color,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TextColors {
+    pub main: Color,
+    pub muted: Color,
+    #[serde(rename = "on-primary")]
+    pub on_primary: Color,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TerminalColors {
+    pub foreground: Color,
+    pub background: Color,
+    pub cursor: Color,
+    pub ansi: AnsiColors,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnsiColors {
+    pub black: Color,
+    pub red: Color,
+    pub green: Color,
+    pub yellow: Color,
+    pub blue: Color,
+    pub magenta: Color,
+    pub cyan: Color,
+    pub white: Color,
+    #[serde(rename = "brightBlack")]
+    pub bright_black: Color,
+    #[serde(rename = "brightRed")]
+    pub bright_red: Color,
+    #[serde(rename = "brightGreen")]
+    pub bright_green: Color,
+    #[serde(rename = "brightYellow")]
+    pub bright_yellow: Color,
+    #[serde(rename = "brightBlue")]
+    pub bright_blue: Color,
+    #[serde(rename = "brightMagenta")]
+    pub bright_magenta: Color,
+    #[serde(rename = "brightCyan")]
+    pub bright_cyan: Color,
+    #[serde(rename = "brightWhite")]
+    pub bright_white: Color,
+}
+
+impl AnsiColors {
+    /// JSON names of the entries, in the order of [`Self::colors`].
+    pub const NAMES: [&'static str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "brightBlack",
+        "brightRed",
+        "brightGreen",
+        "brightYellow",
+        "brightBlue",
+        "brightMagenta",
+        "brightCyan",
+        "brightWhite",
+    ];
+
+    /// The 16 colors in terminal palette order: normal 0-7, bright 8-15.
+    pub fn colors(&self) -> [&Color; 16] {
+        [
+            &self.black,
+            &self.red,
+            &self.green,
+            &self.yellow,
+            &self.blue,
+            &self.magenta,
+            &self.cyan,
+            &self.white,
+            &self.bright_black,
+            &self.bright_red,
+            &self.bright_green,
+            &self.bright_yellow,
+            &self.bright_blue,
+            &self.bright_magenta,
+            &self.bright_cyan,
+            &self.bright_white,
+        ]
+    }
+
+    pub fn colors_mut(&mut self) -> [&mut Color; 16] {
+        [
+            &mut self.black,
+            &mut self.red,
+            &mut self.green,
+            &mut self.yellow,
+            &mut self.blue,
+            &mut self.magenta,
+            &mut self.cyan,
+            &mut self.white,
+            &mut self.bright_black,
+            &mut self.bright_red,
+            &mut self.bright_green,
+            &mut self.bright_yellow,
+            &mut self.bright_blue,
+            &mut self.bright_magenta,
+            &mut self.bright_cyan,
+            &mut self.bright_white,
+        ]
+    }
+}
+
+impl TerminalColors {
+    /// Keys used in [`ThemeVariant::derived`], in the order of
+    /// [`Self::colors_mut`].
+    pub const KEYS: [&'static str; 19] = [
+        "terminal.foreground",
+        "terminal.background",
+        "terminal.cursor",
+        "terminal.ansi.black",
+        "terminal.ansi.red",
+        "terminal.ansi.green",
+        "terminal.ansi.yellow",
+        "terminal.ansi.blue",
+        "terminal.ansi.magenta",
+        "terminal.ansi.cyan",
+        "terminal.ansi.white",
+        "terminal.ansi.brightBlack",
+        "terminal.ansi.brightRed",
+        "terminal.ansi.brightGreen",
+        "terminal.ansi.brightYellow",
+        "terminal.ansi.brightBlue",
+        "terminal.ansi.brightMagenta",
+        "terminal.ansi.brightCyan",
+        "terminal.ansi.brightWhite",
+    ];
+
+    /// Foreground, background, cursor and then the 16 ANSI colors.
+    pub fn colors_mut(&mut self) -> [&mut Color; 19] {
+        let ansi = &mut self.ansi;
+        [
+            &mut self.foreground,
+            &mut self.background,
+            &mut self.cursor,
+            &mut ansi.black,
+            &mut ansi.red,
+            &mut ansi.green,
+            &mut ansi.yellow,
+            &mut ansi.blue,
+            &mut ansi.magenta,
+            &mut ansi.cyan,
+            &mut ansi.white,
+            &mut ansi.bright_black,
+            &mut ansi.bright_red,
+            &mut ansi.bright_green,
+            &mut ansi.bright_yellow,
+            &mut ansi.bright_blue,
+            &mut ansi.bright_magenta,
+            &mut ansi.bright_cyan,
+            &mut ansi.bright_white,
+        ]
+    }
+
+    /// Todas las entradas con el mismo color; se sobrescriben enseguida.
+    fn filled(color: &Color) -> Self {
+        Self {
+            foreground: color.clone(),
+            background: color.clone(),
+            cursor: color.clone(),
+            ansi: AnsiColors {
+                black: color.clone(),
+                red: color.clone(),
+                green: color.clone(),
+                yellow: color.clone(),
+                blue: color.clone(),
+                magenta: color.clone(),
+                cyan: color.clone(),
+                white: color.clone(),
+                bright_black: color.clone(),
+                bright_red: color.clone(),
+                bright_green: color.clone(),
+                bright_yellow: color.clone(),
+                bright_blue: color.clone(),
+                bright_magenta: color.clone(),
+                bright_cyan: color.clone(),
+                bright_white: color.clone(),
+            },
+        }
+    }
+}
+
+impl ThemeVariant {
+    /// Every color of the variant, UI first and then terminal.
+    pub fn colors_mut(&mut self) -> [&mut Color; 27] {
+        let ui = &mut self.ui;
+        let terminal = &mut self.terminal;
+        let ansi = &mut terminal.ansi;
+        [
+            &mut ui.color.primary,
+            &mut ui.color.secondary,
+            &mut ui.text.main,
+            &mut ui.text.muted,
+            &mut ui.text.on_primary,
+            &mut ui.background,
+            &mut ui.border,
+            &mut ui.surface,
+            &mut terminal.foreground,
+            &mut terminal.background,
+            &mut terminal.cursor,
+            &mut ansi.black,
+            &mut ansi.red,
+            &mut ansi.green,
+            &mut ansi.yellow,
+            &mut ansi.blue,
+            &mut ansi.magenta,
+            &mut ansi.cyan,
+            &mut ansi.white,
+            &mut ansi.bright_black,
+            &mut ansi.bright_red,
+            &mut ansi.bright_green,
+            &mut ansi.bright_yellow,
+            &mut ansi.bright_blue,
+            &mut ansi.bright_magenta,
+            &mut ansi.bright_cyan,
+            &mut ansi.bright_white,
+        ]
+    }
+}
+
+impl Scheme {
+    /// Same scheme with every color written in `format`.
+    pub fn normalized(mut self, format: ColorFormat) -> Self {
+        for variant in [&mut self.scheme.colors.dark, &mut self.scheme.colors.light] {
+            for color in variant.colors_mut() {
+                *color = color.normalized(format);
+            }
+        }
+        self
+    }
+}
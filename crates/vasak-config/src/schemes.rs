@@ -0,0 +1,1165 @@
+use notify::{EventKind, RecommendedWatcher, Watcher};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use crate::models::{Scheme, SchemeData, SchemeVariant};
+use crate::store::ConfigStore;
+
+/// A scheme hidden by another one with the same id in a higher-priority file.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowedScheme {
+    pub id: String,
+    pub path: String,
+    pub shadowed_by: String,
+}
+
+/// What happened to a candidate scheme file.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SchemeStatus {
+    /// The file provides the effective scheme for `id`.
+    Loaded { id: String },
+    /// Another file with the same id takes precedence.
+    #[serde(rename_all = "camelCase")]
+    Shadowed { id: String, shadowed_by: String },
+    /// The file is not well-formed JSON.
+    InvalidJson {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// The JSON lacks a required field.
+    MissingField {
+        field: String,
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// The JSON is well-formed but does not match the scheme format.
+    InvalidScheme {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// The file could not be read.
+    Unreadable { message: String },
+    /// `extends` names a scheme that does not exist.
+    MissingParent { id: String, parent: String },
+    /// The parent exists but could not be resolved itself.
+    InvalidParent { id: String, parent: String },
+    /// Following `extends` from this scheme never ends; `chain` lists the
+    /// ids that form the loop.
+    InheritanceCycle { id: String, chain: Vec<String> },
+    /// Hidden files (`.name.json`) are ignored.
+    Hidden,
+    /// File names that are not valid UTF-8 are ignored.
+    NonUtf8Name,
+}
+
+/// Diagnostic entry for one candidate file in the scheme directories.
+#[derive(Debug, Serialize, Clone)]
+pub struct SchemeDiagnostic {
+    pub path: String,
+    #[serde(flatten)]
+    pub status: SchemeStatus,
+}
+
+/// A scheme file as written, before `extends` is resolved.
+#[derive(Debug, Serialize, Clone)]
+pub struct RawScheme {
+    pub path: String,
+    pub scheme: serde_json::Value,
+}
+
+/// Scheme ids affected by a change in the scheme directories.
+///
+/// Ids refer to the effective schemes: a file that starts or stops shadowing
+/// another one shows up as `modified`.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct SchemeChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+impl SchemeChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Whether the scheme with `id` was added, removed or modified.
+    pub fn affects(&self, id: &str) -> bool {
+        self.added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.modified)
+            .any(|changed| changed == id)
+    }
+
+    fn between(before: &HashMap<String, Scheme>, after: &HashMap<String, Scheme>) -> Self {
+        let mut changes = Self::default();
+        for (id, scheme) in after {
+            match before.get(id) {
+                None => changes.added.push(id.clone()),
+                Some(previous) if previous != scheme => changes.modified.push(id.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = before
+            .keys()
+            .filter(|id| !after.contains_key(*id))
+            .cloned()
+            .collect();
+
+        changes.added.sort();
+        changes.removed.sort();
+        changes.modified.sort();
+        changes
+    }
+}
+
+/// Keeps the scheme directory watches alive; dropping it stops them.
+pub struct SchemeWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Debug, Clone)]
+struct SchemeFile {
+    dir: usize,
+    /// El esquema, o el motivo por el que el archivo no aporta ninguno.
+    outcome: Result<ParsedScheme, SchemeStatus>,
+}
+
+/// Contenido de un archivo válido, antes de resolver `extends`.
+#[derive(Debug, Clone)]
+struct ParsedScheme {
+    id: String,
+    raw: serde_json::Value,
+    /// El esquema completo cuando no hereda de otro.
+    full: Option<SchemeData>,
+}
+
+impl ParsedScheme {
+    fn extends(&self) -> Option<&str> {
+        self.raw.get("extends").and_then(serde_json::Value::as_str)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct RegistryState {
+    dirs: Vec<PathBuf>,
+    loaded: bool,
+    files: BTreeMap<PathBuf, SchemeFile>,
+    by_id: HashMap<String, Scheme>,
+    shadowed: Vec<ShadowedScheme>,
+    /// Archivos cuyo `extends` no se pudo resolver.
+    errors: HashMap<PathBuf, SchemeStatus>,
+}
+
+/// Rutas acumuladas durante la ventana de debounce del watcher.
+#[derive(Default)]
+struct PendingRefresh {
+    paths: BTreeSet<PathBuf>,
+    rescan: bool,
+    scheduled: bool,
+}
+
+/// In-memory index of the color schemes, keyed by id.
+///
+/// The scheme directories are scanned once; afterwards [`SchemeRegistry::watch`]
+/// re-parses only the files that change. When two files share an id, the one
+/// in the earlier directory wins (see [`ConfigStore::effective_scheme_paths`]),
+/// and within a directory the first file name in lexical order.
+#[derive(Clone, Default)]
+pub struct SchemeRegistry {
+    state: Arc<RwLock<RegistryState>>,
+    /// Serializa los refrescos, que leen los archivos sin bloquear `state`.
+    refresh: Arc<Mutex<()>>,
+    listener: Arc<RwLock<Option<ChangeListener>>>,
+}
+
+type ChangeListener = Arc<dyn Fn(&SchemeChanges) + Send + Sync>;
+
+fn is_scheme_file(path: &Path) -> bool {
+    #[cfg(feature = "scheme-import")]
+    if crate::import::ImportFormat::from_path(path).is_some() {
+        return true;
+    }
+    path.extension().and_then(|ext| ext.to_str()) == Some("json")
+}
+
+/// Archivos que se listan en el diagnóstico pero nunca se leen.
+fn skip_reason(path: &Path) -> Option<SchemeStatus> {
+    match path.file_name()?.to_str() {
+        None => Some(SchemeStatus::NonUtf8Name),
+        Some(name) if name.starts_with('.') => Some(SchemeStatus::Hidden),
+        Some(_) => None,
+    }
+}
+
+fn parse_error_status(e: &serde_json::Error) -> SchemeStatus {
+    let (line, column) = (e.line(), e.column());
+    // El Display de serde_json agrega la posición, que ya va en campos propios.
+    let full = e.to_string();
+    let message = full
+        .strip_suffix(&format!(" at line {} column {}", line, column))
+        .unwrap_or(&full)
+        .to_string();
+
+    match e.classify() {
+        serde_json::error::Category::Data => {
+            let field = message
+                .strip_prefix("missing field `")
+                .and_then(|rest| rest.split('`').next())
+                .map(str::to_string);
+            match field {
+                Some(field) => SchemeStatus::MissingField {
+                    field,
+                    message,
+                    line,
+                    column,
+                },
+                None => SchemeStatus::InvalidScheme {
+                    message,
+                    line,
+                    column,
+                },
+            }
+        }
+        _ => SchemeStatus::InvalidJson {
+            message,
+            line,
+            column,
+        },
+    }
+}
+
+fn parse_scheme(
+    path: &Path,
+    content: std::io::Result<String>,
+) -> Result<ParsedScheme, SchemeStatus> {
+    let content = content.map_err(|e| {
+        eprintln!("[SchemeRegistry] Could not read {}: {}", path.display(), e);
+        SchemeStatus::Unreadable {
+            message: e.to_string(),
+        }
+    })?;
+
+    #[cfg(feature = "scheme-import")]
+    if let Some(format) = crate::import::ImportFormat::from_path(path) {
+        return import_scheme_file(path, &content, format);
+    }
+
+    let invalid = |e: serde_json::Error| {
+        eprintln!(
+            "[SchemeRegistry] Invalid scheme JSON in {}: {}",
+            path.display(),
+            e
+        );
+        parse_error_status(&e)
+    };
+
+    let raw: serde_json::Value = serde_json::from_str(&content).map_err(invalid)?;
+    match raw.get("extends") {
+        // Sin herencia el archivo tiene que ser un esquema completo; se
+        // deserializa desde el texto para conservar línea y columna.
+        None | Some(serde_json::Value::Null) => {
+            let full = serde_json::from_str::<SchemeData>(&content).map_err(invalid)?;
+            Ok(ParsedScheme {
+                id: full.id.clone(),
+                raw,
+                full: Some(full),
+            })
+        }
+        Some(serde_json::Value::String(_)) => {
+            let id = raw
+                .get("id")
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| SchemeStatus::MissingField {
+                    field: "id".to_string(),
+                    message: "missing field `id`".to_string(),
+                    line: 0,
+                    column: 0,
+                })?
+                .to_string();
+            Ok(ParsedScheme {
+                id,
+                raw,
+                full: None,
+            })
+        }
+        Some(_) => Err(SchemeStatus::InvalidScheme {
+            message: "`extends` must be a scheme id".to_string(),
+            line: 0,
+            column: 0,
+        }),
+    }
+}
+
+/// Los esquemas base16/base24 e iTerm2 se convierten al leerlos; su id sale
+/// del nombre del archivo.
+#[cfg(feature = "scheme-import")]
+fn import_scheme_file(
+    path: &Path,
+    content: &str,
+    format: crate::import::ImportFormat,
+) -> Result<ParsedScheme, SchemeStatus> {
+    let id = crate::import::scheme_id_for(path);
+    let full = crate::import::convert(content, format, &id).map_err(|message| {
+        eprintln!(
+            "[SchemeRegistry] Cannot import scheme {}: {}",
+            path.display(),
+            message
+        );
+        SchemeStatus::InvalidScheme {
+            message,
+            line: 0,
+            column: 0,
+        }
+    })?;
+    let raw = serde_json::to_value(&full).map_err(|e| parse_error_status(&e))?;
+    Ok(ParsedScheme {
+        id: full.id.clone(),
+        raw,
+        full: Some(full),
+    })
+}
+
+/// Aplica `overlay` sobre `base`: los objetos se combinan por clave y el
+/// resto de los valores se reemplaza.
+fn merge_json(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Resuelve `extends` para los archivos ya ordenados por prioridad.
+struct Resolver<'a> {
+    files: &'a [(&'a PathBuf, &'a ParsedScheme)],
+    candidates: HashMap<&'a str, Vec<usize>>,
+    resolved: HashMap<usize, Result<SchemeData, SchemeStatus>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(files: &'a [(&'a PathBuf, &'a ParsedScheme)]) -> Self {
+        let mut candidates: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, (_, parsed)) in files.iter().enumerate() {
+            candidates
+                .entry(parsed.id.as_str())
+                .or_default()
+                .push(index);
+        }
+        Self {
+            files,
+            candidates,
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Archivos candidatos a padre, en orden de prioridad. Un esquema que
+    /// extiende su propio ID hereda de los archivos a los que oculta.
+    fn parents_of(&self, index: usize, parent: &str) -> Vec<usize> {
+        let Some(candidates) = self.candidates.get(parent) else {
+            return Vec::new();
+        };
+        if parent == self.files[index].1.id {
+            candidates
+                .iter()
+                .copied()
+                .filter(|&candidate| candidate > index)
+                .collect()
+        } else {
+            candidates.clone()
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        index: usize,
+        stack: &mut Vec<usize>,
+    ) -> Result<SchemeData, SchemeStatus> {
+        if let Some(result) = self.resolved.get(&index) {
+            return result.clone();
+        }
+
+        let parsed = self.files[index].1;
+        let result = match (&parsed.full, parsed.extends()) {
+            (Some(full), _) => Ok(full.clone()),
+            (None, None) => unreachable!("partial schemes always extend another one"),
+            (None, Some(parent_id)) => {
+                stack.push(index);
+                let result = self.resolve_child(index, parent_id, stack);
+                stack.pop();
+                result
+            }
+        };
+
+        self.resolved.insert(index, result.clone());
+        result
+    }
+
+    fn resolve_child(
+        &mut self,
+        index: usize,
+        parent_id: &str,
+        stack: &mut Vec<usize>,
+    ) -> Result<SchemeData, SchemeStatus> {
+        let parsed = self.files[index].1;
+        let parents = self.parents_of(index, parent_id);
+        if parents.is_empty() {
+            return Err(SchemeStatus::MissingParent {
+                id: parsed.id.clone(),
+                parent: parent_id.to_string(),
+            });
+        }
+
+        // El padre es el primer candidato que resuelve, igual que en el índice;
+        // si ninguno lo hace se informa el error del primero.
+        let mut first_error = None;
+        let mut base = None;
+        for parent in parents {
+            match self.resolve_parent(index, parent, parent_id, stack) {
+                Ok(resolved) => {
+                    base = Some(resolved);
+                    break;
+                }
+                Err(status) => {
+                    first_error.get_or_insert(status);
+                }
+            }
+        }
+        let Some(base) = base else {
+            return Err(first_error.expect("at least one parent candidate was tried"));
+        };
+
+        let mut merged = serde_json::to_value(&base).map_err(|e| parse_error_status(&e))?;
+        merge_json(&mut merged, &parsed.raw);
+        keep_explicit_terminal(&mut merged, &parsed.raw);
+        serde_json::from_value::<SchemeData>(merged).map_err(|e| parse_error_status(&e))
+    }
+
+    fn resolve_parent(
+        &mut self,
+        index: usize,
+        parent: usize,
+        parent_id: &str,
+        stack: &mut Vec<usize>,
+    ) -> Result<SchemeData, SchemeStatus> {
+        let parsed = self.files[index].1;
+        if let Some(start) = stack.iter().position(|&visited| visited == parent) {
+            let mut chain: Vec<String> = stack[start..]
+                .iter()
+                .map(|&visited| self.files[visited].1.id.clone())
+                .collect();
+            chain.push(parent_id.to_string());
+            return Err(SchemeStatus::InheritanceCycle {
+                id: parsed.id.clone(),
+                chain,
+            });
+        }
+
+        match self.resolve(parent, stack) {
+            Ok(base) => Ok(base),
+            Err(SchemeStatus::InheritanceCycle { chain, .. }) => {
+                Err(SchemeStatus::InheritanceCycle {
+                    id: parsed.id.clone(),
+                    chain,
+                })
+            }
+            Err(_) => Err(SchemeStatus::InvalidParent {
+                id: parsed.id.clone(),
+                parent: parent_id.to_string(),
+            }),
+        }
+    }
+}
+
+/// Las entradas de terminal que el hijo escribe dejan de ser derivadas,
+/// aunque el padre las derivara.
+fn keep_explicit_terminal(merged: &mut serde_json::Value, child: &serde_json::Value) {
+    for variant in SchemeVariant::ALL {
+        let Some(terminal) = child.pointer(&format!("/colors/{}/terminal", variant.as_str()))
+        else {
+            continue;
+        };
+        let child_derived = child
+            .pointer(&format!("/colors/{}/derived", variant.as_str()))
+            .and_then(serde_json::Value::as_array);
+
+        let mut explicit = Vec::new();
+        if let Some(entries) = terminal.as_object() {
+            for (key, value) in entries {
+                match value.as_object() {
+                    Some(ansi) if key == "ansi" => {
+                        explicit.extend(ansi.keys().map(|name| format!("terminal.ansi.{}", name)))
+                    }
+                    _ => explicit.push(format!("terminal.{}", key)),
+                }
+            }
+        }
+        explicit.retain(|key| {
+            !child_derived.is_some_and(|derived| derived.iter().any(|entry| entry == key.as_str()))
+        });
+
+        let derived = merged
+            .pointer_mut(&format!("/colors/{}/derived", variant.as_str()))
+            .and_then(serde_json::Value::as_array_mut);
+        if let Some(derived) = derived {
+            derived.retain(|entry| {
+                !explicit
+                    .iter()
+                    .any(|key| entry.as_str() == Some(key.as_str()))
+            });
+        }
+    }
+}
+
+fn read_scheme_file(path: &Path) -> Result<ParsedScheme, SchemeStatus> {
+    if let Some(reason) = skip_reason(path) {
+        return Err(reason);
+    }
+    parse_scheme(path, std::fs::read_to_string(path))
+}
+
+impl RegistryState {
+    fn dir_index(&self, path: &Path) -> Option<usize> {
+        let parent = path.parent()?;
+        self.dirs.iter().position(|dir| dir == parent)
+    }
+
+    /// Vuelve a leer un archivo, o lo quita si ya no existe.
+    fn refresh_file(&mut self, path: &Path) {
+        let Some(dir) = self.dir_index(path) else {
+            return;
+        };
+
+        if path.is_file() && is_scheme_file(path) {
+            let outcome = read_scheme_file(path);
+            self.files
+                .insert(path.to_path_buf(), SchemeFile { dir, outcome });
+        } else {
+            self.files.remove(path);
+        }
+    }
+
+    fn rebuild_index(&mut self) {
+        let mut ordered: Vec<(&PathBuf, &SchemeFile)> = self.files.iter().collect();
+        ordered.sort_by_key(|(path, file)| (file.dir, path.as_path()));
+        let parsed: Vec<(&PathBuf, &ParsedScheme)> = ordered
+            .into_iter()
+            .filter_map(|(path, file)| file.outcome.as_ref().ok().map(|parsed| (path, parsed)))
+            .collect();
+
+        let mut resolver = Resolver::new(&parsed);
+        let mut by_id: HashMap<String, Scheme> = HashMap::new();
+        let mut shadowed = Vec::new();
+        let mut errors = HashMap::new();
+        for (index, (path, file)) in parsed.iter().enumerate() {
+            let path_text = path.to_string_lossy().to_string();
+            // Un archivo que no resuelve no oculta a los siguientes.
+            if let Some(winner) = by_id.get(&file.id) {
+                shadowed.push(ShadowedScheme {
+                    id: file.id.clone(),
+                    path: path_text,
+                    shadowed_by: winner.path.clone(),
+                });
+                continue;
+            }
+
+            match resolver.resolve(index, &mut Vec::new()) {
+                Ok(scheme) => {
+                    by_id.insert(
+                        file.id.clone(),
+                        Scheme {
+                            path: path_text,
+                            scheme,
+                        },
+                    );
+                }
+                Err(status) => {
+                    errors.insert((*path).clone(), status);
+                }
+            }
+        }
+
+        self.by_id = by_id;
+        self.shadowed = shadowed;
+        self.errors = errors;
+    }
+}
+
+impl SchemeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read_state(&self) -> std::sync::RwLockReadGuard<'_, RegistryState> {
+        self.state
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write_state(&self) -> std::sync::RwLockWriteGuard<'_, RegistryState> {
+        self.state
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_refresh(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.refresh
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Scheme directories in priority order, resolved on first use.
+    pub fn directories(&self) -> crate::Result<Vec<PathBuf>> {
+        self.resolve_dirs()
+    }
+
+    /// Resuelve las rutas efectivas la primera vez y asegura que existan.
+    fn resolve_dirs(&self) -> crate::Result<Vec<PathBuf>> {
+        {
+            let state = self.read_state();
+            if !state.dirs.is_empty() {
+                return Ok(state.dirs.clone());
+            }
+        }
+
+        let dirs = ConfigStore::effective_scheme_paths()?;
+        for dir in &dirs {
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                eprintln!(
+                    "[SchemeRegistry] Could not ensure schemes directory {}: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        }
+
+        let mut state = self.write_state();
+        if state.dirs.is_empty() {
+            state.dirs = dirs;
+        }
+        Ok(state.dirs.clone())
+    }
+
+    /// Scans every scheme directory, unless that was already done.
+    pub async fn ensure_loaded(&self) -> crate::Result<()> {
+        if self.read_state().loaded {
+            return Ok(());
+        }
+        self.reload().await
+    }
+
+    /// Discards the index and scans every scheme directory again.
+    pub async fn reload(&self) -> crate::Result<()> {
+        let dirs = self.resolve_dirs()?;
+        let mut files = BTreeMap::new();
+
+        for (index, dir) in dirs.iter().enumerate() {
+            let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+                eprintln!(
+                    "[SchemeRegistry] Could not read schemes directory {}",
+                    dir.display()
+                );
+                continue;
+            };
+
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                let is_file = entry
+                    .metadata()
+                    .await
+                    .map(|metadata| metadata.is_file())
+                    .unwrap_or(false);
+                if !is_file || !is_scheme_file(&path) {
+                    continue;
+                }
+
+                let outcome = match skip_reason(&path) {
+                    Some(reason) => Err(reason),
+                    None => parse_scheme(&path, tokio::fs::read_to_string(&path).await),
+                };
+                files.insert(
+                    path,
+                    SchemeFile {
+                        dir: index,
+                        outcome,
+                    },
+                );
+            }
+        }
+
+        let _refresh = self.lock_refresh();
+        let mut state = self.write_state();
+        state.files = files;
+        state.loaded = true;
+        state.rebuild_index();
+        Ok(())
+    }
+
+    /// Like [`Self::ensure_loaded`], scanning from the calling thread.
+    pub fn ensure_loaded_blocking(&self) -> crate::Result<()> {
+        if self.read_state().loaded {
+            return Ok(());
+        }
+
+        let dirs = self.resolve_dirs()?;
+        let mut files = BTreeMap::new();
+        for (index, dir) in dirs.iter().enumerate() {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                eprintln!(
+                    "[SchemeRegistry] Could not read schemes directory {}",
+                    dir.display()
+                );
+                continue;
+            };
+
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if !path.is_file() || !is_scheme_file(&path) {
+                    continue;
+                }
+                let outcome = read_scheme_file(&path);
+                files.insert(
+                    path,
+                    SchemeFile {
+                        dir: index,
+                        outcome,
+                    },
+                );
+            }
+        }
+
+        let _refresh = self.lock_refresh();
+        let mut state = self.write_state();
+        // Otro hilo pudo terminar su escaneo mientras tanto.
+        if !state.loaded {
+            state.files = files;
+            state.loaded = true;
+            state.rebuild_index();
+        }
+        Ok(())
+    }
+
+    /// Re-parses the given files and updates the index. Paths outside the
+    /// scheme directories are ignored.
+    ///
+    /// The listener installed by [`Self::watch`] is called when the
+    /// effective schemes change, whoever triggered the refresh.
+    pub async fn refresh_paths(&self, paths: Vec<PathBuf>) -> crate::Result<SchemeChanges> {
+        let registry = self.clone();
+        tokio::task::spawn_blocking(move || registry.refresh_paths_blocking(&paths))
+            .await
+            .map_err(|e| crate::Error::Other(format!("Scheme refresh failed: {}", e)))
+    }
+
+    /// Like [`Self::refresh_paths`], reading the files from the calling thread.
+    pub fn refresh_paths_blocking(&self, paths: &[PathBuf]) -> SchemeChanges {
+        let changes = {
+            let _refresh = self.lock_refresh();
+            // Se lee y resuelve sobre una copia: el índice solo se bloquea
+            // para reemplazarlo.
+            let mut next = self.read_state().clone();
+            // Sin escaneo inicial no hay nada que invalidar: se leerá todo al cargar.
+            if !next.loaded {
+                return SchemeChanges::default();
+            }
+            for path in paths {
+                next.refresh_file(path);
+            }
+            next.rebuild_index();
+
+            let mut state = self.write_state();
+            let changes = SchemeChanges::between(&state.by_id, &next.by_id);
+            *state = next;
+            changes
+        };
+
+        if !changes.is_empty() {
+            let listener = self
+                .listener
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone();
+            if let Some(listener) = listener {
+                listener(&changes);
+            }
+        }
+        changes
+    }
+
+    /// Re-reads every known file and every scheme file in the scheme
+    /// directories, from the calling thread.
+    fn rescan_blocking(&self) -> SchemeChanges {
+        let mut paths: Vec<PathBuf> = self.read_state().files.keys().cloned().collect();
+        for dir in self.read_state().dirs.clone() {
+            if let Ok(entries) = std::fs::read_dir(&dir) {
+                paths.extend(
+                    entries
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| is_scheme_file(path)),
+                );
+            }
+        }
+        self.refresh_paths_blocking(&paths)
+    }
+
+    /// Effective schemes, one per id, in directory priority order.
+    pub fn schemes(&self) -> Vec<Scheme> {
+        let state = self.read_state();
+        let mut schemes: Vec<(usize, Scheme)> = state
+            .by_id
+            .values()
+            .map(|scheme| {
+                let dir = state
+                    .dir_index(Path::new(&scheme.path))
+                    .unwrap_or(usize::MAX);
+                (dir, scheme.clone())
+            })
+            .collect();
+        schemes.sort_by(|(a_dir, a), (b_dir, b)| (a_dir, &a.path).cmp(&(b_dir, &b.path)));
+        schemes.into_iter().map(|(_, scheme)| scheme).collect()
+    }
+
+    /// The effective scheme with `id`, if any.
+    pub fn get(&self, id: &str) -> Option<Scheme> {
+        self.read_state().by_id.get(id).cloned()
+    }
+
+    /// Every candidate file in the scheme directories with its status, in
+    /// directory priority order.
+    pub fn diagnostics(&self) -> Vec<SchemeDiagnostic> {
+        let state = self.read_state();
+        let mut ordered: Vec<(&PathBuf, &SchemeFile)> = state.files.iter().collect();
+        ordered.sort_by_key(|(path, file)| (file.dir, path.as_path()));
+
+        ordered
+            .into_iter()
+            .map(|(file_path, file)| {
+                let path = file_path.to_string_lossy().to_string();
+                let status = match &file.outcome {
+                    Ok(parsed) => match (
+                        state.errors.get(file_path.as_path()),
+                        state.by_id.get(&parsed.id),
+                    ) {
+                        (Some(error), _) => error.clone(),
+                        (None, Some(winner)) if winner.path != path => SchemeStatus::Shadowed {
+                            id: parsed.id.clone(),
+                            shadowed_by: winner.path.clone(),
+                        },
+                        _ => SchemeStatus::Loaded {
+                            id: parsed.id.clone(),
+                        },
+                    },
+                    Err(status) => status.clone(),
+                };
+                SchemeDiagnostic { path, status }
+            })
+            .collect()
+    }
+
+    /// The effective file for `id` as written, with `extends` unresolved.
+    pub fn get_raw(&self, id: &str) -> Option<RawScheme> {
+        let state = self.read_state();
+        let path = &state.by_id.get(id)?.path;
+        let file = state.files.get(Path::new(path))?;
+        let parsed = file.outcome.as_ref().ok()?;
+        Some(RawScheme {
+            path: path.clone(),
+            scheme: parsed.raw.clone(),
+        })
+    }
+
+    /// Schemes hidden by another file with the same id.
+    pub fn shadowed(&self) -> Vec<ShadowedScheme> {
+        self.read_state().shadowed.clone()
+    }
+
+    /// Watches each scheme directory, keeps the index up to date and calls
+    /// `on_change` with the affected ids, both for external edits and for
+    /// refreshes requested through [`Self::refresh_paths`].
+    ///
+    /// The directories are scanned first if no lookup did it yet, so that
+    /// changes arriving before the first lookup are reported too.
+    ///
+    /// Events are batched for a short window so that editors writing a file
+    /// in several steps produce a single notification. Directories that do
+    /// not exist (and could not be created) are skipped.
+    pub fn watch<F>(&self, on_change: F) -> crate::Result<SchemeWatcher>
+    where
+        F: Fn(&SchemeChanges) + Send + Sync + 'static,
+    {
+        let dirs = self.resolve_dirs()?;
+        self.ensure_loaded_blocking()?;
+        let registry = self.clone();
+        *self
+            .listener
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(on_change));
+        let pending = Arc::new(Mutex::new(PendingRefresh::default()));
+        let debounce_window = Duration::from_millis(250);
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("[SchemeRegistry] Error watching schemes: {:?}", e);
+                    return;
+                }
+            };
+
+            let rescan = event.need_rescan();
+            let paths: Vec<PathBuf> = if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                event
+                    .paths
+                    .into_iter()
+                    .filter(|path| is_scheme_file(path))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            if !rescan && paths.is_empty() {
+                return;
+            }
+
+            let mut guard = pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            guard.paths.extend(paths);
+            // El kernel perdió eventos: solo un escaneo completo es fiable.
+            guard.rescan |= rescan;
+            if guard.scheduled {
+                return;
+            }
+            guard.scheduled = true;
+            drop(guard);
+
+            let registry = registry.clone();
+            let pending = pending.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(debounce_window);
+                let batch = {
+                    let mut guard = pending
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    std::mem::take(&mut *guard)
+                };
+
+                // `refresh_paths_blocking` avisa al listener si hubo cambios.
+                if batch.rescan {
+                    registry.rescan_blocking();
+                } else {
+                    registry.refresh_paths_blocking(&batch.paths.into_iter().collect::<Vec<_>>());
+                }
+            });
+        })
+        .map_err(|e| crate::Error::Other(format!("Cannot create schemes watcher: {}", e)))?;
+
+        for dir in &dirs {
+            if !dir.is_dir() {
+                continue;
+            }
+            if let Err(e) = watcher.watch(dir, notify::RecursiveMode::NonRecursive) {
+                eprintln!(
+                    "[SchemeRegistry] Failed to watch schemes directory {}: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(SchemeWatcher { _watcher: watcher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::generate::{generate_scheme, GenerateOptions};
+
+    fn full_scheme(id: &str) -> String {
+        let scheme = generate_scheme(
+            &Color::parse("#3584e4").unwrap(),
+            &GenerateOptions {
+                id: Some(id.to_string()),
+                ..Default::default()
+            },
+        );
+        serde_json::to_string(&scheme).unwrap()
+    }
+
+    fn child(id: &str, parent: &str) -> String {
+        serde_json::json!({
+            "id": id,
+            "extends": parent,
+            "name": id,
+            "colors": { "dark": { "ui": { "color": { "primary": "#ff0000" } } } }
+        })
+        .to_string()
+    }
+
+    /// Índice de los archivos `(directorio, nombre, contenido)`.
+    fn state_with(files: &[(usize, &str, String)]) -> RegistryState {
+        let mut state = RegistryState {
+            dirs: vec![PathBuf::from("/user"), PathBuf::from("/system")],
+            loaded: true,
+            ..Default::default()
+        };
+        for (dir, name, content) in files {
+            let path = state.dirs[*dir].join(name);
+            let outcome = parse_scheme(&path, Ok(content.clone()));
+            state.files.insert(path, SchemeFile { dir: *dir, outcome });
+        }
+        state.rebuild_index();
+        state
+    }
+
+    fn error_of(state: &RegistryState, path: &str) -> SchemeStatus {
+        state.errors.get(Path::new(path)).cloned().unwrap()
+    }
+
+    #[test]
+    fn child_overrides_only_what_it_writes() {
+        let state = state_with(&[
+            (0, "red.json", child("red", "blue")),
+            (1, "blue.json", full_scheme("blue")),
+        ]);
+        let parent = &state.by_id["blue"].scheme;
+        let scheme = &state.by_id["red"].scheme;
+
+        assert_eq!(scheme.extends.as_deref(), Some("blue"));
+        assert_eq!(scheme.name, "red");
+        assert_eq!(scheme.colors.dark.ui.color.primary.as_str(), "#ff0000");
+        assert_eq!(
+            scheme.colors.dark.ui.background,
+            parent.colors.dark.ui.background
+        );
+        assert_eq!(scheme.colors.light, parent.colors.light);
+    }
+
+    #[test]
+    fn extending_its_own_id_inherits_from_the_shadowed_file() {
+        let state = state_with(&[
+            (0, "blue.json", child("blue", "blue")),
+            (1, "blue.json", full_scheme("blue")),
+        ]);
+        let scheme = &state.by_id["blue"];
+
+        assert_eq!(scheme.path, "/user/blue.json");
+        assert_eq!(
+            scheme.scheme.colors.dark.ui.color.primary.as_str(),
+            "#ff0000"
+        );
+        assert_eq!(state.shadowed.len(), 1);
+        assert_eq!(state.shadowed[0].path, "/system/blue.json");
+    }
+
+    #[test]
+    fn missing_parent_is_reported() {
+        let state = state_with(&[(0, "red.json", child("red", "nowhere"))]);
+
+        assert!(state.by_id.is_empty());
+        assert_eq!(
+            error_of(&state, "/user/red.json"),
+            SchemeStatus::MissingParent {
+                id: "red".to_string(),
+                parent: "nowhere".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn cycles_are_reported_with_their_chain() {
+        let state = state_with(&[
+            (0, "a.json", child("a", "b")),
+            (0, "b.json", child("b", "a")),
+        ]);
+
+        assert!(state.by_id.is_empty());
+        assert_eq!(
+            error_of(&state, "/user/a.json"),
+            SchemeStatus::InheritanceCycle {
+                id: "a".to_string(),
+                chain: vec!["a".to_string(), "b".to_string(), "a".to_string()],
+            }
+        );
+        assert!(matches!(
+            error_of(&state, "/user/b.json"),
+            SchemeStatus::InheritanceCycle { id, .. } if id == "b"
+        ));
+    }
+
+    #[test]
+    fn extending_a_broken_scheme_is_an_invalid_parent() {
+        let state = state_with(&[
+            (0, "a.json", child("a", "b")),
+            (0, "b.json", child("b", "nowhere")),
+        ]);
+
+        assert_eq!(
+            error_of(&state, "/user/a.json"),
+            SchemeStatus::InvalidParent {
+                id: "a".to_string(),
+                parent: "b".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn a_file_that_does_not_resolve_does_not_shadow_the_next_one() {
+        let state = state_with(&[
+            (0, "blue.json", child("blue", "nowhere")),
+            (1, "blue.json", full_scheme("blue")),
+        ]);
+
+        assert_eq!(state.by_id["blue"].path, "/system/blue.json");
+        assert!(state.shadowed.is_empty());
+    }
+
+    #[test]
+    fn extends_resolves_against_the_first_parent_that_resolves() {
+        let state = state_with(&[
+            (0, "blue.json", child("blue", "nowhere")),
+            (0, "red.json", child("red", "blue")),
+            (1, "blue.json", full_scheme("blue")),
+        ]);
+
+        assert_eq!(state.by_id["blue"].path, "/system/blue.json");
+        assert_eq!(state.by_id["red"].path, "/user/red.json");
+    }
+
+    #[test]
+    fn misspelled_ansi_names_are_rejected() {
+        let mut scheme: serde_json::Value = serde_json::from_str(&full_scheme("blue")).unwrap();
+        scheme["colors"]["dark"]["terminal"]["ansi"]["brightred"] = "#ff0000".into();
+        let state = state_with(&[(0, "blue.json", scheme.to_string())]);
+
+        assert!(state.by_id.is_empty());
+        let outcome = &state.files[Path::new("/user/blue.json")].outcome;
+        assert!(matches!(
+            outcome,
+            Err(SchemeStatus::InvalidScheme { message, .. }) if message.contains("brightred")
+        ));
+    }
+}
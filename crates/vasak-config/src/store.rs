@@ -0,0 +1,813 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex as AsyncMutex, RwLock};
+
+#[cfg(feature = "system-theme-sync")]
+use std::process::Command;
+
+use crate::contrast::{ContrastOptions, ContrastReport};
+use crate::models::*;
+use crate::schemes::{RawScheme, SchemeChanges, SchemeDiagnostic, SchemeRegistry, SchemeWatcher};
+
+/// Access to the Vasak config and color schemes with an internal TTL cache.
+///
+/// It does not depend on any UI runtime: callers that need to notify others
+/// about a change use the config returned by the write methods.
+#[derive(Clone)]
+pub struct ConfigStore {
+    cache: Arc<RwLock<Option<CacheEntry>>>,
+    write_lock: Arc<AsyncMutex<()>>,
+    changes: broadcast::Sender<VSKConfig>,
+    schemes: SchemeRegistry,
+    scheme_write_lock: Arc<AsyncMutex<()>>,
+    ttl: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    content: String,
+    timestamp: Instant,
+}
+
+impl Default for ConfigStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigStore {
+    fn config_path_from_env() -> Option<std::path::PathBuf> {
+        std::env::var_os("VASAK_CONFIG_PATH").and_then(|value| {
+            let path = std::path::PathBuf::from(value);
+            if path.as_os_str().is_empty() {
+                None
+            } else {
+                Some(path)
+            }
+        })
+    }
+
+    fn default_scheme_paths() -> crate::Result<Vec<std::path::PathBuf>> {
+        Ok(vec![
+            Self::home_dir()?.join(".config/vasak/schemes"),
+            std::path::PathBuf::from("/usr/share/schemes"),
+        ])
+    }
+
+    fn scheme_paths_from_env() -> Option<Vec<std::path::PathBuf>> {
+        let raw = std::env::var_os("VASAK_SCHEMES_PATHS")?;
+        let paths: Vec<std::path::PathBuf> = std::env::split_paths(&raw)
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect();
+
+        if paths.is_empty() {
+            None
+        } else {
+            Some(paths)
+        }
+    }
+
+    /// Scheme directories in lookup priority order.
+    pub fn effective_scheme_paths() -> crate::Result<Vec<std::path::PathBuf>> {
+        if let Some(paths) = Self::scheme_paths_from_env() {
+            return Ok(paths);
+        }
+
+        Self::default_scheme_paths()
+    }
+
+    /// Replaces `path` with `content` through a synced temporary file and a rename.
+    pub async fn write_file_atomically(path: &std::path::Path, content: &str) -> crate::Result<()> {
+        let path = path.to_path_buf();
+        let content = content.to_string();
+        tokio::task::spawn_blocking(move || Self::write_file_atomically_blocking(&path, &content))
+            .await
+            .map_err(|e| crate::Error::Other(format!("Write task failed: {}", e)))?
+    }
+
+    /// Like [`Self::write_file_atomically`], from the calling thread.
+    pub fn write_file_atomically_blocking(
+        path: &std::path::Path,
+        content: &str,
+    ) -> crate::Result<()> {
+        use std::io::Write;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let parent = path.parent().ok_or_else(|| {
+            crate::Error::Other(format!("Path has no parent directory: {}", path.display()))
+        })?;
+
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| crate::Error::Other(format!("System time error: {}", e)))?
+            .as_nanos();
+        // Oculto y sin extensión `.json`, para que ningún watcher lo tome por
+        // un esquema o por la configuración.
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let tmp_path = parent.join(format!(
+            ".{}.tmp-{}-{}",
+            file_name,
+            std::process::id(),
+            nonce
+        ));
+
+        let mut tmp_file = std::fs::File::create(&tmp_path).map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to create temporary file {}: {}",
+                    tmp_path.display(),
+                    e
+                ),
+            ))
+        })?;
+
+        if let Err(e) = tmp_file.write_all(content.as_bytes()) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to write temporary file {}: {}",
+                    tmp_path.display(),
+                    e
+                ),
+            )));
+        }
+
+        if let Err(e) = tmp_file.sync_all() {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to sync temporary file {}: {}",
+                    tmp_path.display(),
+                    e
+                ),
+            )));
+        }
+
+        drop(tmp_file);
+
+        std::fs::rename(&tmp_path, path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to atomically replace {}: {}", path.display(), e),
+            ))
+        })
+    }
+
+    pub fn new() -> Self {
+        // Default TTL de 30 minutos para evitar lecturas de disco frecuentes.
+        Self {
+            cache: Arc::new(RwLock::new(None)),
+            write_lock: Arc::new(AsyncMutex::new(())),
+            changes: broadcast::channel(16).0,
+            schemes: SchemeRegistry::new(),
+            scheme_write_lock: Arc::new(AsyncMutex::new(())),
+            ttl: Duration::from_secs(30 * 60),
+        }
+    }
+
+    /// Receives every config persisted through this store, and every external
+    /// change picked up by [`Self::reload_from_file`].
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<VSKConfig> {
+        self.changes.subscribe()
+    }
+
+    fn notify_changed(&self, config: &VSKConfig) {
+        // Sin receptores `send` falla; no es un error para el store.
+        let _ = self.changes.send(config.clone());
+    }
+
+    fn home_dir() -> crate::Result<std::path::PathBuf> {
+        dirs_next::home_dir().ok_or_else(|| {
+            crate::Error::Other("No se pudo obtener el directorio home del usuario".to_string())
+        })
+    }
+
+    /// Returns true if the cache is present and not expired.
+    async fn is_cache_valid(&self) -> bool {
+        let guard = self.cache.read().await;
+        if let Some(entry) = guard.as_ref() {
+            entry.timestamp.elapsed() < self.ttl
+        } else {
+            false
+        }
+    }
+
+    /// Read configuration using cache-first strategy.
+    pub async fn read_config(&self) -> crate::Result<String> {
+        if self.is_cache_valid().await {
+            let guard = self.cache.read().await;
+            if let Some(entry) = guard.as_ref() {
+                return Ok(entry.content.clone());
+            }
+        }
+
+        // Cache inválido o inexistente: leer de disco y actualizar cache.
+        let config_path = self.config_path()?;
+
+        // Si el archivo no existe, crearlo con una configuración por defecto
+        if !config_path.exists() {
+            let _write_guard = self.write_lock.lock().await;
+            if !config_path.exists() {
+                self.create_default_config().await?;
+            }
+        }
+
+        let config_content = tokio::fs::read_to_string(&config_path).await.map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read config file {}: {}",
+                    config_path.display(),
+                    e
+                ),
+            ))
+        })?;
+
+        {
+            let mut guard = self.cache.write().await;
+            *guard = Some(CacheEntry {
+                content: config_content.clone(),
+                timestamp: Instant::now(),
+            });
+        }
+
+        Ok(config_content)
+    }
+
+    /// Reads the config and parses it.
+    pub async fn load_config(&self) -> crate::Result<VSKConfig> {
+        let content = self.read_config().await?;
+        serde_json::from_str(&content).map_err(crate::Error::Json)
+    }
+
+    /// Like [`Self::load_config`], without a runtime: takes the cache if it is
+    /// valid and free, or reads the file from the calling thread. A missing
+    /// file is an error; it is only created by the async readers.
+    pub fn load_config_blocking(&self) -> crate::Result<VSKConfig> {
+        if let Ok(guard) = self.cache.try_read() {
+            if let Some(entry) = guard
+                .as_ref()
+                .filter(|entry| entry.timestamp.elapsed() < self.ttl)
+            {
+                return serde_json::from_str(&entry.content).map_err(crate::Error::Json);
+            }
+        }
+
+        let config_path = self.config_path()?;
+        let content = std::fs::read_to_string(&config_path).map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read config file {}: {}",
+                    config_path.display(),
+                    e
+                ),
+            ))
+        })?;
+        let config = serde_json::from_str(&content).map_err(crate::Error::Json)?;
+        if let Ok(mut guard) = self.cache.try_write() {
+            *guard = Some(CacheEntry {
+                content,
+                timestamp: Instant::now(),
+            });
+        }
+        Ok(config)
+    }
+
+    /// Validates and persists `config`, returning the parsed value.
+    pub async fn write_config(&self, config: &str) -> crate::Result<VSKConfig> {
+        let config_path = self.config_path()?;
+
+        // Validar semánticamente el payload antes de persistir.
+        let parsed_config: VSKConfig = serde_json::from_str(config).map_err(crate::Error::Json)?;
+
+        let _write_guard = self.write_lock.lock().await;
+
+        // Aplicar icon pack en runtime según el modo actual guardado.
+        Self::try_apply_icon_pack(&parsed_config.icons, parsed_config.style.darkmode);
+
+        self.persist(config_path.as_path(), config).await?;
+        self.notify_changed(&parsed_config);
+        Ok(parsed_config)
+    }
+
+    /// Writes `content` to the config file and refreshes the cache.
+    /// The caller must hold `write_lock`.
+    async fn persist(&self, config_path: &std::path::Path, content: &str) -> crate::Result<()> {
+        // Crear el directorio padre si no existe
+        if let Some(parent) = config_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                crate::Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!(
+                        "Failed to create config directory {}: {}",
+                        parent.display(),
+                        e
+                    ),
+                ))
+            })?;
+        }
+
+        Self::write_file_atomically(config_path, content).await?;
+        // Actualizar cache inmediatamente con el contenido provisto
+        let mut guard = self.cache.write().await;
+        *guard = Some(CacheEntry {
+            content: content.to_string(),
+            timestamp: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Returns the config value at a JSON pointer (`""` for the whole config).
+    pub async fn get_value(&self, pointer: &str) -> crate::Result<Option<serde_json::Value>> {
+        let content = self.read_config().await?;
+        let config: serde_json::Value =
+            serde_json::from_str(&content).map_err(crate::Error::Json)?;
+        Ok(config.pointer(pointer).cloned())
+    }
+
+    /// Sets the value at a JSON pointer and persists the result.
+    ///
+    /// The last segment may name a key that does not exist yet in its parent
+    /// object; the updated document must still be a valid [`VSKConfig`].
+    /// Changing `/style/darkmode` syncs the GNOME theme like
+    /// [`Self::set_darkmode`].
+    pub async fn set_value(
+        &self,
+        pointer: &str,
+        value: serde_json::Value,
+    ) -> crate::Result<VSKConfig> {
+        let config_path = self.config_path()?;
+        let _write_guard = self.write_lock.lock().await;
+
+        if !config_path.exists() {
+            self.create_default_config().await?;
+        }
+
+        let config_content = tokio::fs::read_to_string(&config_path).await.map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read config file {}: {}",
+                    config_path.display(),
+                    e
+                ),
+            ))
+        })?;
+        let mut document: serde_json::Value =
+            serde_json::from_str(&config_content).map_err(crate::Error::Json)?;
+
+        let was_dark = document
+            .pointer("/style/darkmode")
+            .and_then(serde_json::Value::as_bool);
+        set_pointer(&mut document, pointer, value)?;
+
+        let config: VSKConfig =
+            serde_json::from_value(document.clone()).map_err(crate::Error::Json)?;
+        // Cambiar el modo por puntero sincroniza GNOME igual que `set_darkmode`.
+        if was_dark != Some(config.style.darkmode) {
+            Self::try_sync_system_darkmode(config.style.darkmode);
+        }
+        Self::try_apply_icon_pack(&config.icons, config.style.darkmode);
+
+        let new_content = serde_json::to_string_pretty(&document).map_err(crate::Error::Json)?;
+        self.persist(config_path.as_path(), &new_content).await?;
+        self.notify_changed(&config);
+        Ok(config)
+    }
+
+    /// Re-applies the system side of the current config (GNOME theme and icons).
+    pub async fn apply_system_theme(&self) -> crate::Result<VSKConfig> {
+        let config = self.load_config().await?;
+        Self::try_sync_system_darkmode(config.style.darkmode);
+        Self::try_apply_icon_pack(&config.icons, config.style.darkmode);
+        self.log_sync_errors().await;
+        Ok(config)
+    }
+
+    pub fn config_path(&self) -> crate::Result<std::path::PathBuf> {
+        if let Some(path) = Self::config_path_from_env() {
+            return Ok(path);
+        }
+
+        Ok(Self::home_dir()?.join(".config/vasak/vasak.conf"))
+    }
+
+    #[cfg(feature = "system-theme-sync")]
+    fn run_gsettings(args: &[&str]) -> crate::Result<String> {
+        let output = Command::new("gsettings").args(args).output().map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to run gsettings {}: {}", args.join(" "), e),
+            ))
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let detail = if stderr.is_empty() { stdout } else { stderr };
+            return Err(crate::Error::Io(std::io::Error::other(format!(
+                "gsettings {} failed: {}",
+                args.join(" "),
+                detail
+            ))));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    #[cfg(feature = "system-theme-sync")]
+    fn has_gsettings_binary() -> bool {
+        Command::new("gsettings").arg("help").output().is_ok()
+    }
+
+    #[cfg(feature = "system-theme-sync")]
+    /// Syncs GNOME's color-scheme and gtk-theme through gsettings when available.
+    pub fn try_sync_system_darkmode(darkmode: bool) {
+        if !Self::has_gsettings_binary() {
+            eprintln!(
+                "[ConfigManager::set_darkmode] gsettings not found; skipping system theme sync"
+            );
+            return;
+        }
+
+        let current_scheme_raw = match Self::run_gsettings(&[
+            "get",
+            "org.gnome.desktop.interface",
+            "color-scheme",
+        ]) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!(
+                    "[ConfigManager::set_darkmode] Could not read system color-scheme via gsettings: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let current_scheme = current_scheme_raw
+            .trim_matches('"')
+            .trim_matches('\'')
+            .to_string();
+
+        if darkmode && current_scheme != "prefer-dark" {
+            if let Err(e) = Self::run_gsettings(&[
+                "set",
+                "org.gnome.desktop.interface",
+                "color-scheme",
+                "prefer-dark",
+            ]) {
+                eprintln!(
+                    "[ConfigManager::set_darkmode] Could not set GNOME color-scheme to prefer-dark: {}",
+                    e
+                );
+                return;
+            }
+
+            if let Err(e) = Self::run_gsettings(&[
+                "set",
+                "org.gnome.desktop.interface",
+                "gtk-theme",
+                "Adwaita-dark",
+            ]) {
+                eprintln!(
+                    "[ConfigManager::set_darkmode] Could not set GNOME gtk-theme to Adwaita-dark: {}",
+                    e
+                );
+            }
+        } else if !darkmode && current_scheme != "prefer-light" {
+            if let Err(e) = Self::run_gsettings(&[
+                "set",
+                "org.gnome.desktop.interface",
+                "color-scheme",
+                "prefer-light",
+            ]) {
+                eprintln!(
+                    "[ConfigManager::set_darkmode] Could not set GNOME color-scheme to prefer-light: {}",
+                    e
+                );
+                return;
+            }
+
+            if let Err(e) =
+                Self::run_gsettings(&["set", "org.gnome.desktop.interface", "gtk-theme", "Adwaita"])
+            {
+                eprintln!(
+                    "[ConfigManager::set_darkmode] Could not set GNOME gtk-theme to Adwaita: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    #[cfg(not(feature = "system-theme-sync"))]
+    pub fn try_sync_system_darkmode(_darkmode: bool) {}
+
+    #[cfg(feature = "system-theme-sync")]
+    /// Applies the icon theme matching `darkmode` through gsettings when available.
+    pub fn try_apply_icon_pack(icons: &Icons, darkmode: bool) {
+        if !Self::has_gsettings_binary() {
+            return;
+        }
+
+        let selected_pack = if darkmode {
+            icons.dark.trim()
+        } else {
+            icons.light.trim()
+        };
+
+        if selected_pack.is_empty() {
+            return;
+        }
+
+        if let Err(e) = Self::run_gsettings(&[
+            "set",
+            "org.gnome.desktop.interface",
+            "icon-theme",
+            selected_pack,
+        ]) {
+            eprintln!(
+                "[ConfigManager] Could not set icon theme to '{}': {}",
+                selected_pack, e
+            );
+        }
+    }
+
+    #[cfg(not(feature = "system-theme-sync"))]
+    pub fn try_apply_icon_pack(_icons: &Icons, _darkmode: bool) {}
+
+    /// Persists the dark mode flag and returns the updated config. The applied
+    /// terminal, GTK, Qt and X11 themes follow through
+    /// [`Self::follow_theme_changes`].
+    pub async fn set_darkmode(&self, darkmode: bool) -> crate::Result<VSKConfig> {
+        let _write_guard = self.write_lock.lock().await;
+
+        // Intentamos sincronizar con GNOME si está disponible, pero sin bloquear
+        // la persistencia de configuración cuando no existe gsettings o falla.
+        Self::try_sync_system_darkmode(darkmode);
+
+        let config_path = self.config_path()?;
+
+        // Si el archivo no existe, crearlo con una configuración por defecto
+        if !config_path.exists() {
+            self.create_default_config().await?;
+        }
+
+        let config_content = tokio::fs::read_to_string(&config_path).await.map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read config file {}: {}",
+                    config_path.display(),
+                    e
+                ),
+            ))
+        })?;
+
+        let mut config: VSKConfig =
+            serde_json::from_str(&config_content).map_err(crate::Error::Json)?;
+
+        config.style.darkmode = darkmode;
+
+        // Aplicar icon pack asociado al modo actual (dark/light).
+        Self::try_apply_icon_pack(&config.icons, darkmode);
+
+        let new_content = serde_json::to_string_pretty(&config).map_err(crate::Error::Json)?;
+
+        Self::write_file_atomically(config_path.as_path(), &new_content).await?;
+        // Actualizar cache con el nuevo contenido
+        {
+            let mut guard = self.cache.write().await;
+            *guard = Some(CacheEntry {
+                content: serde_json::to_string_pretty(&config).map_err(crate::Error::Json)?,
+                timestamp: Instant::now(),
+            });
+        }
+        // Los temas aplicados (terminal, GTK, Qt) los sincroniza quien siga los
+        // cambios (`follow_theme_changes`), fuera de este lock.
+        self.notify_changed(&config);
+        Ok(config)
+    }
+
+    /// Clears the cache.
+    pub async fn clear_cache(&self) {
+        let mut guard = self.cache.write().await;
+        *guard = None;
+    }
+
+    /// Refreshes the cache from disk.
+    pub async fn refresh_cache_from_file(&self) -> crate::Result<()> {
+        self.reload_from_file().await.map(|_| ())
+    }
+
+    /// Refreshes the cache from disk and returns `true` if the content changed.
+    ///
+    /// The store's own writes already update the cache, so a watcher can use
+    /// the result to tell them apart from external changes.
+    pub async fn reload_from_file(&self) -> crate::Result<bool> {
+        let config_path = self.config_path()?;
+
+        // Tomar el lock de escritura para no comparar contra un cache que una
+        // escritura en curso todavía no actualizó.
+        let _write_guard = self.write_lock.lock().await;
+
+        // Si el archivo no existe, crearlo con una configuración por defecto
+        if !config_path.exists() {
+            self.create_default_config().await?;
+        }
+
+        let content = tokio::fs::read_to_string(&config_path).await.map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read config file {}: {}",
+                    config_path.display(),
+                    e
+                ),
+            ))
+        })?;
+        let parsed = serde_json::from_str::<VSKConfig>(&content);
+        let changed = {
+            let mut guard = self.cache.write().await;
+            let changed = guard
+                .as_ref()
+                .map(|entry| entry.content != content)
+                .unwrap_or(true);
+            *guard = Some(CacheEntry {
+                content,
+                timestamp: Instant::now(),
+            });
+            changed
+        };
+
+        if changed {
+            if let Ok(config) = parsed {
+                self.notify_changed(&config);
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Crea el archivo de configuración con valores por defecto.
+    async fn create_default_config(&self) -> crate::Result<()> {
+        let config_path = self.config_path()?;
+
+        // Crear el directorio padre si no existe
+        if let Some(parent) = config_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                crate::Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!(
+                        "Failed to create config directory {}: {}",
+                        parent.display(),
+                        e
+                    ),
+                ))
+            })?;
+        }
+
+        // Configuración por defecto
+        let default_config = VSKConfig {
+            style: Style {
+                darkmode: false,
+                color_scheme: "vasak-default".to_string(),
+                radius: 8,
+            },
+            desktop: Some(Desktop {
+                wallpaper: vec![],
+                iconsize: 48,
+                showfiles: true,
+                showhiddenfiles: false,
+            }),
+            fonts: Fonts {
+                termina: String::new(),
+                title: String::new(),
+                apps: String::new(),
+            },
+            icons: Icons {
+                dark: String::new(),
+                light: String::new(),
+            },
+        };
+
+        let config_content =
+            serde_json::to_string_pretty(&default_config).map_err(crate::Error::Json)?;
+
+        Self::write_file_atomically(config_path.as_path(), &config_content).await?;
+
+        Ok(())
+    }
+
+    /// Scheme index shared by every clone of the store.
+    pub fn scheme_registry(&self) -> &SchemeRegistry {
+        &self.schemes
+    }
+
+    /// Serializa las escrituras en los directorios de esquemas.
+    pub(crate) fn scheme_write_lock(&self) -> &AsyncMutex<()> {
+        &self.scheme_write_lock
+    }
+
+    /// Keeps the scheme index up to date by watching every scheme directory,
+    /// and calls `on_change` with the affected ids.
+    pub fn watch_schemes<F>(&self, on_change: F) -> crate::Result<SchemeWatcher>
+    where
+        F: Fn(&SchemeChanges) + Send + Sync + 'static,
+    {
+        self.schemes.watch(on_change)
+    }
+
+    /// The effective schemes (one per id) of the effective scheme paths.
+    ///
+    /// The directories are only scanned the first time; without
+    /// [`Self::watch_schemes`], later changes are not seen until
+    /// [`SchemeRegistry::reload`] is called.
+    pub async fn load_schemes(&self) -> crate::Result<Vec<Scheme>> {
+        self.schemes.ensure_loaded().await?;
+        Ok(self.schemes.schemes())
+    }
+
+    /// The effective file of a scheme as written, with `extends` unresolved.
+    pub async fn get_raw_scheme(&self, scheme_id: &str) -> crate::Result<Option<RawScheme>> {
+        self.schemes.ensure_loaded().await?;
+        Ok(self.schemes.get_raw(scheme_id))
+    }
+
+    /// Every candidate file in the scheme directories with its status:
+    /// loaded, shadowed by another, invalid or skipped.
+    pub async fn scheme_diagnostics(&self) -> crate::Result<Vec<SchemeDiagnostic>> {
+        self.schemes.ensure_loaded().await?;
+        Ok(self.schemes.diagnostics())
+    }
+
+    /// The effective scheme with `scheme_id`.
+    /// Priority:
+    /// 1) the order of VASAK_SCHEMES_PATHS (if set)
+    /// 2) the default order: ~/.config/vasak/schemes, then /usr/share/schemes
+    pub async fn get_scheme_by_id(&self, scheme_id: &str) -> crate::Result<Option<Scheme>> {
+        self.schemes.ensure_loaded().await?;
+        Ok(self.schemes.get(scheme_id))
+    }
+
+    /// Audits the WCAG contrast (and APCA, if requested) of the text pairs of
+    /// both variants of the scheme `scheme_id`.
+    pub async fn check_scheme_contrast(
+        &self,
+        scheme_id: &str,
+        options: ContrastOptions,
+    ) -> crate::Result<ContrastReport> {
+        let scheme = self
+            .get_scheme_by_id(scheme_id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", scheme_id)))?;
+        Ok(crate::contrast::check_scheme_contrast(
+            &scheme.scheme,
+            options,
+        ))
+    }
+}
+
+fn set_pointer(
+    document: &mut serde_json::Value,
+    pointer: &str,
+    value: serde_json::Value,
+) -> crate::Result<()> {
+    if pointer.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+
+    if let Some(target) = document.pointer_mut(pointer) {
+        *target = value;
+        return Ok(());
+    }
+
+    let (parent_pointer, key) = pointer
+        .rsplit_once('/')
+        .ok_or_else(|| crate::Error::Other(format!("Invalid JSON pointer: {}", pointer)))?;
+    let key = key.replace("~1", "/").replace("~0", "~");
+
+    match document.pointer_mut(parent_pointer) {
+        Some(serde_json::Value::Object(map)) => {
+            map.insert(key, value);
+            Ok(())
+        }
+        Some(_) => Err(crate::Error::Other(format!(
+            "Cannot set {}: parent is not an object",
+            pointer
+        ))),
+        None => Err(crate::Error::Other(format!(
+            "Cannot set {}: parent {} does not exist",
+            pointer, parent_pointer
+        ))),
+    }
+}
@@ -0,0 +1,775 @@
+//! Writes exported themes where each application reads them, and keeps them
+//! in sync with the active scheme and dark mode.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+
+use crate::export::{render, ExportContext, ExportFormat};
+use crate::ini::{self, IniEntry};
+use crate::models::{SchemeVariant, VSKConfig};
+use crate::store::ConfigStore;
+
+/// Archivo, junto a `vasak.conf`, con los formatos que el usuario aplicó.
+const APPLIED_THEMES_FILE: &str = "applied-themes.json";
+
+/// Nombre con el que las aplicaciones listan el tema aplicado; no cambia con
+/// el esquema para que su configuración no tenga que tocarse.
+const APPLIED_THEME_TITLE: &str = "Vasak";
+
+/// Marcas del bloque que se reescribe en archivos que también edita el
+/// usuario, como `gtk.css`; lo que quede fuera no se toca. Cada formato las
+/// escribe con su sintaxis de comentario.
+struct BlockMarkers {
+    begin: &'static str,
+    end: &'static str,
+}
+
+const CSS_BLOCK: BlockMarkers = BlockMarkers {
+    begin: "/* BEGIN vasak-config managed block: edits here are overwritten */",
+    end: "/* END vasak-config managed block */",
+};
+
+const XRESOURCES_BLOCK: BlockMarkers = BlockMarkers {
+    begin: "! BEGIN vasak-config managed block: edits here are overwritten",
+    end: "! END vasak-config managed block",
+};
+
+const XSETTINGSD_BLOCK: BlockMarkers = BlockMarkers {
+    begin: "# BEGIN vasak-config managed block: edits here are overwritten",
+    end: "# END vasak-config managed block",
+};
+
+/// Result of applying one format.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedTheme {
+    pub format: ExportFormat,
+    /// File written, or the dconf path loaded.
+    pub target: String,
+    /// Why this format could not be applied; the others are still applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Dónde lee cada aplicación su tema.
+enum Target {
+    File(PathBuf),
+    /// Bloque con marcas dentro de un archivo del usuario.
+    ManagedBlock {
+        path: PathBuf,
+        markers: &'static BlockMarkers,
+    },
+    /// Archivo propio y las claves que lo activan en la configuración de la
+    /// aplicación.
+    Activated {
+        file: PathBuf,
+        settings: PathBuf,
+        entries: Vec<IniEntry>,
+    },
+    Dconf(String),
+}
+
+impl Target {
+    fn describe(&self) -> String {
+        match self {
+            Target::File(path) | Target::ManagedBlock { path, .. } => path.display().to_string(),
+            Target::Activated { file, .. } => file.display().to_string(),
+            Target::Dconf(path) => format!("dconf:{}", path),
+        }
+    }
+}
+
+/// Directorios base del usuario en los que se buscan los destinos.
+struct BaseDirs {
+    config: PathBuf,
+    data: PathBuf,
+    home: PathBuf,
+}
+
+impl BaseDirs {
+    fn from_env() -> crate::Result<Self> {
+        let missing = |what: &str| crate::Error::Other(format!("Cannot resolve the {}", what));
+        Ok(Self {
+            config: dirs_next::config_dir().ok_or_else(|| missing("XDG config directory"))?,
+            data: dirs_next::data_dir().ok_or_else(|| missing("XDG data directory"))?,
+            home: dirs_next::home_dir().ok_or_else(|| missing("home directory"))?,
+        })
+    }
+}
+
+/// `'uuid'` del perfil por defecto de GNOME Terminal.
+fn gnome_terminal_profile() -> crate::Result<String> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.Terminal.ProfilesList", "default"])
+        .output()
+        .map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to run gsettings: {}", e),
+            ))
+        })?;
+    let profile = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_matches('\'')
+        .to_string();
+
+    if !output.status.success() || profile.is_empty() {
+        return Err(crate::Error::Other(
+            "No default GNOME Terminal profile".to_string(),
+        ));
+    }
+    Ok(profile)
+}
+
+/// `kdeglobals` guarda una copia de los colores del esquema activo, que es la
+/// que leen las aplicaciones Qt; `ColorScheme` solo lo nombra.
+fn kdeglobals_entries(content: &str) -> Vec<IniEntry> {
+    let mut entries: Vec<IniEntry> = ini::parse(content)
+        .into_iter()
+        .filter(|entry| entry.group.starts_with("Colors:") || entry.group == "WM")
+        .collect();
+    entries.push(IniEntry::new("General", "ColorScheme", APPLIED_THEME_TITLE));
+    entries
+}
+
+fn qtct_target(config: &Path, tool: &str) -> Target {
+    let file = config.join(format!("{}/colors/vasak.conf", tool));
+    let entries = vec![
+        IniEntry::new("Appearance", "color_scheme_path", &file.to_string_lossy()),
+        IniEntry::new("Appearance", "custom_palette", "true"),
+    ];
+    Target::Activated {
+        settings: config.join(format!("{}/{}.conf", tool, tool)),
+        file,
+        entries,
+    }
+}
+
+fn target_for(format: ExportFormat, content: &str) -> crate::Result<Target> {
+    target_in(&BaseDirs::from_env()?, format, content)
+}
+
+fn target_in(dirs: &BaseDirs, format: ExportFormat, content: &str) -> crate::Result<Target> {
+    let config = &dirs.config;
+    Ok(match format {
+        ExportFormat::Alacritty => Target::File(config.join("alacritty/vasak.toml")),
+        ExportFormat::Kitty => Target::File(config.join("kitty/vasak-theme.conf")),
+        ExportFormat::Foot => Target::File(config.join("foot/vasak-theme.ini")),
+        ExportFormat::Wezterm => Target::File(config.join("wezterm/colors/vasak.toml")),
+        ExportFormat::GnomeTerminal => Target::Dconf(format!(
+            "/org/gnome/terminal/legacy/profiles:/:{}/",
+            gnome_terminal_profile()?
+        )),
+        ExportFormat::Gtk3 => Target::ManagedBlock {
+            path: config.join("gtk-3.0/gtk.css"),
+            markers: &CSS_BLOCK,
+        },
+        ExportFormat::Gtk4 => Target::ManagedBlock {
+            path: config.join("gtk-4.0/gtk.css"),
+            markers: &CSS_BLOCK,
+        },
+        ExportFormat::Kde => Target::Activated {
+            file: dirs
+                .data
+                .join(format!("color-schemes/{}.colors", APPLIED_THEME_TITLE)),
+            settings: config.join("kdeglobals"),
+            entries: kdeglobals_entries(content),
+        },
+        ExportFormat::Qt5ct => qtct_target(config, "qt5ct"),
+        ExportFormat::Qt6ct => qtct_target(config, "qt6ct"),
+        ExportFormat::Xresources => Target::ManagedBlock {
+            path: dirs.home.join(".Xresources"),
+            markers: &XRESOURCES_BLOCK,
+        },
+        ExportFormat::Xsettingsd => Target::ManagedBlock {
+            path: config.join("xsettingsd/xsettingsd.conf"),
+            markers: &XSETTINGSD_BLOCK,
+        },
+        ExportFormat::Vscode
+        | ExportFormat::Neovim
+        | ExportFormat::Base16
+        | ExportFormat::Base24
+        | ExportFormat::Itermcolors => {
+            return Err(crate::Error::Other(format!(
+                "{} themes can only be exported",
+                format.as_str()
+            )))
+        }
+    })
+}
+
+/// Sustituye el bloque gestionado de `existing` por `content`, o lo añade al
+/// final para que sus definiciones prevalezcan sobre las anteriores. `None`
+/// si hay marca de inicio sin la de cierre: un bloque nuevo se emparejaría
+/// con ella en la próxima escritura y borraría lo que el usuario tiene entre
+/// ambas.
+fn replace_managed_block(existing: &str, content: &str, markers: &BlockMarkers) -> Option<String> {
+    let block = format!(
+        "{}\n{}\n{}\n",
+        markers.begin,
+        content.trim_end(),
+        markers.end
+    );
+
+    if let Some(begin) = existing.find(markers.begin) {
+        let offset = existing[begin..].find(markers.end)?;
+        let mut end = begin + offset + markers.end.len();
+        if existing[end..].starts_with('\n') {
+            end += 1;
+        }
+        return Some(format!(
+            "{}{}{}",
+            &existing[..begin],
+            block,
+            &existing[end..]
+        ));
+    }
+
+    Some(match existing {
+        "" => block,
+        _ if existing.ends_with('\n') => format!("{}\n{}", existing, block),
+        _ => format!("{}\n\n{}", existing, block),
+    })
+}
+
+/// Pide a las aplicaciones X11 en marcha que relean sus ajustes. Es un
+/// extra: sin sesión X o sin xsettingsd, los leen al arrancar.
+fn reload_x11(format: ExportFormat, path: &Path) {
+    let mut command = match format {
+        ExportFormat::Xresources if std::env::var_os("DISPLAY").is_some() => {
+            let mut command = Command::new("xrdb");
+            command.arg("-merge").arg(path);
+            command
+        }
+        ExportFormat::Xsettingsd => {
+            let mut command = Command::new("pkill");
+            command.args(["-HUP", "-x", "xsettingsd"]);
+            command
+        }
+        _ => return,
+    };
+    let _ = command.stdout(Stdio::null()).stderr(Stdio::null()).status();
+}
+
+/// Busca el ejecutable de la aplicación en `PATH`.
+fn is_installed(format: ExportFormat) -> bool {
+    let program = match format {
+        ExportFormat::Wezterm => "wezterm-gui",
+        other => other.as_str(),
+    };
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn dconf_load(path: &str, content: &str) -> crate::Result<()> {
+    let failed = |message: String| crate::Error::Other(format!("dconf load {}: {}", path, message));
+
+    let mut child = Command::new("dconf")
+        .args(["load", path])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(content.as_bytes())
+            .map_err(|e| failed(e.to_string()))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Contenido de un archivo que la aplicación quizá aún no creó.
+fn read_or_empty(path: &Path) -> crate::Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(crate::Error::Io(e)),
+    }
+}
+
+fn variant_for(config: &VSKConfig) -> SchemeVariant {
+    if config.style.darkmode {
+        SchemeVariant::Dark
+    } else {
+        SchemeVariant::Light
+    }
+}
+
+/// Escribe un formato y avisa a las aplicaciones X11 en marcha; bloquea.
+fn apply_format(context: &ExportContext<'_>, format: ExportFormat) -> AppliedTheme {
+    let content = render(context, format);
+    let (target, result) = match target_for(format, &content) {
+        Ok(target) => {
+            let result = write_target(&target, &content);
+            if let (Ok(()), Target::ManagedBlock { path, .. }) = (&result, &target) {
+                reload_x11(format, path);
+            }
+            (target.describe(), result)
+        }
+        Err(e) => (String::new(), Err(e)),
+    };
+    AppliedTheme {
+        format,
+        target,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+fn write_target(target: &Target, content: &str) -> crate::Result<()> {
+    match target {
+        Target::File(path) => write_if_changed(path, content),
+        Target::ManagedBlock { path, markers } => {
+            let existing = read_or_empty(path)?;
+            let updated = replace_managed_block(&existing, content, markers).ok_or_else(|| {
+                crate::Error::Other(format!(
+                    "{} has the line {:?} but not {:?}; add or remove it by hand",
+                    path.display(),
+                    markers.begin,
+                    markers.end
+                ))
+            })?;
+            write_if_changed(path, &updated)
+        }
+        Target::Activated {
+            file,
+            settings,
+            entries,
+        } => {
+            write_if_changed(file, content)?;
+            let existing = read_or_empty(settings)?;
+            write_if_changed(settings, &ini::set_entries(&existing, entries))
+        }
+        Target::Dconf(path) => dconf_load(path, content),
+    }
+}
+
+fn write_if_changed(path: &Path, content: &str) -> crate::Result<()> {
+    // Sin cambios no se reescribe, para no despertar a quien vigila el archivo.
+    if std::fs::read_to_string(path).ok().as_deref() == Some(content) {
+        return Ok(());
+    }
+    // El rename reemplazaría un enlace simbólico (p. ej. a un repo de
+    // dotfiles) por un archivo normal: se escribe en el destino real.
+    if let Ok(resolved) = std::fs::canonicalize(path) {
+        return ConfigStore::write_file_atomically_blocking(&resolved, content);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    ConfigStore::write_file_atomically_blocking(path, content)
+}
+
+impl ConfigStore {
+    /// Renders a scheme in `format`. Without `variant`, the one matching the
+    /// current dark mode. xsettingsd output takes the icon theme and fonts
+    /// from the config.
+    pub async fn export_scheme(
+        &self,
+        scheme_id: &str,
+        variant: Option<SchemeVariant>,
+        format: ExportFormat,
+    ) -> crate::Result<String> {
+        let scheme = self
+            .get_scheme_by_id(scheme_id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", scheme_id)))?;
+        let config = self.load_config().await?;
+        let context = ExportContext {
+            scheme: &scheme.scheme,
+            variant: variant.unwrap_or_else(|| variant_for(&config)),
+            title: &scheme.scheme.name,
+            config: Some(&config),
+        };
+        Ok(render(&context, format))
+    }
+
+    fn applied_themes_path(&self) -> crate::Result<PathBuf> {
+        let config_path = self.config_path()?;
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| crate::Error::Other("Invalid config path".to_string()))?;
+        Ok(dir.join(APPLIED_THEMES_FILE))
+    }
+
+    /// Formats applied so far; they are rewritten on every theme change.
+    pub async fn applied_themes(&self) -> crate::Result<Vec<ExportFormat>> {
+        let path = self.applied_themes_path()?;
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).map_err(crate::Error::Json),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(crate::Error::Io(e)),
+        }
+    }
+
+    async fn remember_applied_themes(&self, formats: &[ExportFormat]) -> crate::Result<()> {
+        let mut applied = self.applied_themes().await?;
+        let before = applied.len();
+        for format in formats {
+            if !applied.contains(format) {
+                applied.push(*format);
+            }
+        }
+        if applied.len() == before {
+            return Ok(());
+        }
+
+        let content = serde_json::to_string_pretty(&applied).map_err(crate::Error::Json)?;
+        Self::write_file_atomically(&self.applied_themes_path()?, &content).await
+    }
+
+    /// Escribe cada formato con el esquema activo; un fallo no frena al resto.
+    async fn apply_formats(&self, formats: &[ExportFormat]) -> crate::Result<Vec<AppliedTheme>> {
+        let config = self.load_config().await?;
+        let scheme_id = &config.style.color_scheme;
+        let scheme = self
+            .get_scheme_by_id(scheme_id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", scheme_id)))?
+            .scheme;
+        let formats = formats.to_vec();
+
+        // Los archivos y `dconf`, `xrdb` y `pkill` bloquean: fuera del runtime.
+        tokio::task::spawn_blocking(move || {
+            let context = ExportContext {
+                scheme: &scheme,
+                variant: variant_for(&config),
+                title: APPLIED_THEME_TITLE,
+                config: Some(&config),
+            };
+            formats
+                .into_iter()
+                .map(|format| apply_format(&context, format))
+                .collect()
+        })
+        .await
+        .map_err(|e| crate::Error::Other(format!("Theme write task failed: {}", e)))
+    }
+
+    /// Writes the active scheme, in the variant matching dark mode, for the
+    /// given terminal emulators (by default, those found in `PATH`), and keeps those
+    /// files in sync from then on (see [`Self::follow_theme_changes`]).
+    ///
+    /// Explicit formats are written whether or not the emulator is installed.
+    /// Without formats, fails if none is found in `PATH`. The main config of
+    /// each emulator is not edited: it has to include the written file once
+    /// (`import` in Alacritty, `include` in Kitty and foot, `color_scheme` in
+    /// WezTerm).
+    pub async fn apply_terminal_theme(
+        &self,
+        formats: Option<Vec<ExportFormat>>,
+    ) -> crate::Result<Vec<AppliedTheme>> {
+        let formats = match formats {
+            Some(formats) => formats,
+            None => {
+                let installed: Vec<ExportFormat> = ExportFormat::TERMINALS
+                    .into_iter()
+                    .filter(|&format| is_installed(format))
+                    .collect();
+                if installed.is_empty() {
+                    return Err(crate::Error::Other(
+                        "No supported terminal emulator found in PATH; pass the formats explicitly"
+                            .to_string(),
+                    ));
+                }
+                installed
+            }
+        };
+        if let Some(format) = formats.iter().find(|format| !format.is_terminal()) {
+            return Err(crate::Error::Other(format!(
+                "{} is not a terminal theme format",
+                format.as_str()
+            )));
+        }
+
+        self.remember_applied_themes(&formats).await?;
+        self.apply_formats(&formats).await
+    }
+
+    /// Writes the active scheme as `@define-color` overrides into the managed
+    /// block of `~/.config/gtk-3.0/gtk.css` and `gtk-4.0/gtk.css`, keeping the
+    /// rest of those files, and keeps them in sync from then on (see
+    /// [`Self::follow_theme_changes`]). GTK applications read them on start.
+    pub async fn apply_gtk_theme(&self) -> crate::Result<Vec<AppliedTheme>> {
+        self.remember_applied_themes(&ExportFormat::GTK).await?;
+        self.apply_formats(&ExportFormat::GTK).await
+    }
+
+    /// Writes the active scheme as a KDE color scheme and as qt5ct and qt6ct
+    /// palettes, selects them in `kdeglobals`, `qt5ct.conf` and `qt6ct.conf`,
+    /// and keeps them in sync from then on (see
+    /// [`Self::follow_theme_changes`]).
+    pub async fn apply_qt_theme(&self) -> crate::Result<Vec<AppliedTheme>> {
+        self.remember_applied_themes(&ExportFormat::QT).await?;
+        self.apply_formats(&ExportFormat::QT).await
+    }
+
+    /// Writes the active terminal colors as X resources into the managed
+    /// block of `~/.Xresources`, and the GTK theme, icon theme and fonts into
+    /// the managed block of `~/.config/xsettingsd/xsettingsd.conf`, and keeps
+    /// them in sync from then on (see [`Self::follow_theme_changes`]). A
+    /// running X session merges the resources with `xrdb` and a running
+    /// xsettingsd is told to reload.
+    pub async fn apply_x11_theme(&self) -> crate::Result<Vec<AppliedTheme>> {
+        self.remember_applied_themes(&ExportFormat::X11).await?;
+        self.apply_formats(&ExportFormat::X11).await
+    }
+
+    /// Rewrites every applied format with the active scheme. As in every
+    /// `apply_*` method, the files and the `dconf`, `xrdb` and `pkill` calls
+    /// run on the blocking thread pool.
+    pub async fn sync_applied_themes(&self) -> crate::Result<Vec<AppliedTheme>> {
+        let formats = self.applied_themes().await?;
+        if formats.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.apply_formats(&formats).await
+    }
+
+    /// Whether going from `before` to `after` changes the applied themes: dark
+    /// mode, the active scheme, the fonts or the icon themes.
+    pub fn applied_themes_changed(before: &VSKConfig, after: &VSKConfig) -> bool {
+        // Fuentes e iconos solo los usa xsettingsd, pero van en el mismo archivo.
+        before.style.darkmode != after.style.darkmode
+            || before.style.color_scheme != after.style.color_scheme
+            || before.fonts != after.fonts
+            || before.icons != after.icons
+    }
+
+    /// Calls [`Self::sync_applied_themes`] whenever the config changes in a
+    /// way [`Self::applied_themes_changed`] reports. Changes to the scheme
+    /// files themselves come from the scheme watcher; call it from there too.
+    /// The store keeps its change channel open, so this never returns: spawn
+    /// it on the runtime and abort the task to stop it.
+    pub async fn follow_theme_changes(&self) {
+        let mut changes = self.subscribe_changes();
+        let mut last = self.load_config().await.ok();
+
+        loop {
+            let config = match changes.recv().await {
+                Ok(config) => config,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    match self.load_config().await {
+                        Ok(config) => config,
+                        Err(_) => continue,
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            };
+
+            let changed = last
+                .as_ref()
+                .map_or(true, |last| Self::applied_themes_changed(last, &config));
+            last = Some(config);
+            if changed {
+                self.log_sync_errors().await;
+            }
+        }
+    }
+
+    /// Sincroniza e informa por stderr lo que no se pudo aplicar.
+    pub(crate) async fn log_sync_errors(&self) {
+        match self.sync_applied_themes().await {
+            Ok(applied) => {
+                for theme in applied {
+                    if let Some(error) = theme.error {
+                        eprintln!(
+                            "[ConfigStore] Could not apply the {} theme: {}",
+                            theme.format.as_str(),
+                            error
+                        );
+                    }
+                }
+            }
+            Err(e) => eprintln!("[ConfigStore] Could not sync applied themes: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::generate::{generate_scheme, GenerateOptions};
+
+    fn dirs_in(root: &Path) -> BaseDirs {
+        BaseDirs {
+            config: root.join("config"),
+            data: root.join("data"),
+            home: root.join("home"),
+        }
+    }
+
+    fn rendered(format: ExportFormat) -> String {
+        let scheme = generate_scheme(
+            &Color::parse("#3584e4").unwrap(),
+            &GenerateOptions::default(),
+        );
+        let context = ExportContext {
+            scheme: &scheme,
+            variant: SchemeVariant::Dark,
+            title: APPLIED_THEME_TITLE,
+            config: None,
+        };
+        render(&context, format)
+    }
+
+    fn apply_in(dirs: &BaseDirs, format: ExportFormat) -> String {
+        let content = rendered(format);
+        let target = target_in(dirs, format, &content).unwrap();
+        write_target(&target, &content).unwrap();
+        content
+    }
+
+    #[test]
+    fn managed_block_is_appended_after_user_rules() {
+        let out = replace_managed_block("@define-color accent red;", "a", &CSS_BLOCK).unwrap();
+        assert_eq!(
+            out,
+            format!(
+                "@define-color accent red;\n\n{}\na\n{}\n",
+                CSS_BLOCK.begin, CSS_BLOCK.end
+            )
+        );
+        assert_eq!(
+            replace_managed_block("", "a\n", &CSS_BLOCK).unwrap(),
+            format!("{}\na\n{}\n", CSS_BLOCK.begin, CSS_BLOCK.end)
+        );
+    }
+
+    #[test]
+    fn managed_block_is_replaced_in_place() {
+        let existing = format!(
+            "before\n{}\nold\n{}\nafter\n",
+            XRESOURCES_BLOCK.begin, XRESOURCES_BLOCK.end
+        );
+        let out = replace_managed_block(&existing, "new", &XRESOURCES_BLOCK).unwrap();
+        assert_eq!(
+            out,
+            format!(
+                "before\n{}\nnew\n{}\nafter\n",
+                XRESOURCES_BLOCK.begin, XRESOURCES_BLOCK.end
+            )
+        );
+        assert_eq!(
+            replace_managed_block(&out, "new", &XRESOURCES_BLOCK).unwrap(),
+            out
+        );
+    }
+
+    #[test]
+    fn managed_block_without_end_marker_is_not_touched() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = dirs_in(root.path());
+        let gtk_css = dirs.config.join("gtk-3.0/gtk.css");
+        std::fs::create_dir_all(gtk_css.parent().unwrap()).unwrap();
+        let existing = format!("{}\nold\nwindow {{ margin: 0; }}\n", CSS_BLOCK.begin);
+        std::fs::write(&gtk_css, &existing).unwrap();
+
+        assert_eq!(replace_managed_block(&existing, "new", &CSS_BLOCK), None);
+        let content = rendered(ExportFormat::Gtk3);
+        let target = target_in(&dirs, ExportFormat::Gtk3, &content).unwrap();
+        let error = write_target(&target, &content).unwrap_err();
+
+        assert!(error.to_string().contains("gtk.css"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&gtk_css).unwrap(), existing);
+    }
+
+    #[test]
+    fn kde_scheme_is_written_and_selected_in_kdeglobals() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = dirs_in(root.path());
+        let kdeglobals = dirs.config.join("kdeglobals");
+        std::fs::create_dir_all(&dirs.config).unwrap();
+        std::fs::write(
+            &kdeglobals,
+            "[General]\nColorScheme=BreezeDark\nfixed=Hack,10\n\n[KDE]\nSingleClick=false\n",
+        )
+        .unwrap();
+
+        let content = apply_in(&dirs, ExportFormat::Kde);
+
+        let scheme_file = dirs.data.join("color-schemes/Vasak.colors");
+        assert_eq!(std::fs::read_to_string(scheme_file).unwrap(), content);
+        let written = std::fs::read_to_string(&kdeglobals).unwrap();
+        let entries = ini::parse(&written);
+        assert!(entries.contains(&IniEntry::new("General", "ColorScheme", "Vasak")));
+        assert!(entries.contains(&IniEntry::new("General", "fixed", "Hack,10")));
+        assert!(entries.contains(&IniEntry::new("KDE", "SingleClick", "false")));
+        // La copia de los colores es la que leen las aplicaciones Qt.
+        let colors = ini::parse(&content);
+        let view = colors
+            .iter()
+            .find(|entry| entry.group == "Colors:View")
+            .unwrap();
+        assert!(entries.contains(view));
+    }
+
+    #[test]
+    fn qtct_palettes_are_written_and_selected() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = dirs_in(root.path());
+        let qt5ct_conf = dirs.config.join("qt5ct/qt5ct.conf");
+        std::fs::create_dir_all(qt5ct_conf.parent().unwrap()).unwrap();
+        std::fs::write(
+            &qt5ct_conf,
+            "[Appearance]\ncustom_palette=false\nicon_theme=breeze\n\n[Fonts]\nfixed=Hack\n",
+        )
+        .unwrap();
+
+        for (format, tool) in [
+            (ExportFormat::Qt5ct, "qt5ct"),
+            (ExportFormat::Qt6ct, "qt6ct"),
+        ] {
+            let content = apply_in(&dirs, format);
+            let palette = dirs.config.join(format!("{}/colors/vasak.conf", tool));
+            assert_eq!(std::fs::read_to_string(&palette).unwrap(), content);
+
+            let settings = dirs.config.join(format!("{}/{}.conf", tool, tool));
+            let entries = ini::parse(&std::fs::read_to_string(settings).unwrap());
+            assert!(entries.contains(&IniEntry::new(
+                "Appearance",
+                "color_scheme_path",
+                &palette.to_string_lossy(),
+            )));
+            assert!(entries.contains(&IniEntry::new("Appearance", "custom_palette", "true")));
+        }
+
+        let entries = ini::parse(&std::fs::read_to_string(qt5ct_conf).unwrap());
+        assert!(entries.contains(&IniEntry::new("Appearance", "icon_theme", "breeze")));
+        assert!(entries.contains(&IniEntry::new("Fonts", "fixed", "Hack")));
+    }
+
+    #[test]
+    fn symlinked_targets_are_written_through() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = dirs_in(root.path());
+        let dotfile = root.path().join("dotfiles/gtk.css");
+        std::fs::create_dir_all(dotfile.parent().unwrap()).unwrap();
+        std::fs::write(&dotfile, "window { margin: 0; }\n").unwrap();
+        let gtk_css = dirs.config.join("gtk-3.0/gtk.css");
+        std::fs::create_dir_all(gtk_css.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(&dotfile, &gtk_css).unwrap();
+
+        apply_in(&dirs, ExportFormat::Gtk3);
+
+        assert!(std::fs::symlink_metadata(&gtk_css)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        let written = std::fs::read_to_string(&dotfile).unwrap();
+        assert!(written.starts_with("window { margin: 0; }\n"));
+        assert!(written.contains(CSS_BLOCK.begin));
+    }
+}
@@ -0,0 +1,342 @@
+//! Design tokens for web apps: the active scheme, corner radius and fonts as
+//! W3C Design Tokens (DTCG) JSON, CSS custom properties or SCSS variables.
+//!
+//! Token names are the ones `useConfigStore()` sets in `guest-js`:
+//! `ui-background`, `text-main`, `terminal-ansi-red`... for the light variant
+//! and the same names ending in `-dark` for the dark one, plus
+//! `corner-radius` and `font-terminal`, `font-title` and `font-apps`.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::color::Color;
+use crate::models::{AnsiColors, SchemeData, ThemeVariant, VSKConfig};
+use crate::store::ConfigStore;
+
+/// A file format [`ConfigStore::export_tokens`] can produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenFormat {
+    /// W3C Design Tokens Community Group JSON (format 2025.10).
+    Json,
+    /// CSS custom properties in a `:root` rule.
+    Css,
+    /// SCSS variables.
+    Scss,
+}
+
+impl TokenFormat {
+    pub const ALL: [TokenFormat; 3] = [TokenFormat::Json, TokenFormat::Css, TokenFormat::Scss];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenFormat::Json => "json",
+            TokenFormat::Css => "css",
+            TokenFormat::Scss => "scss",
+        }
+    }
+}
+
+impl std::str::FromStr for TokenFormat {
+    type Err = crate::Error;
+
+    fn from_str(value: &str) -> crate::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| crate::Error::Other(format!("Unknown token format: {}", value)))
+    }
+}
+
+enum TokenValue {
+    Color(Color),
+    /// Píxeles.
+    Dimension(u32),
+    FontFamily(String),
+}
+
+struct Token {
+    /// Sin el `--` de CSS ni el `$` de SCSS.
+    name: String,
+    value: TokenValue,
+}
+
+impl Token {
+    fn color(name: String, color: &Color) -> Self {
+        Token {
+            name,
+            value: TokenValue::Color(color.clone()),
+        }
+    }
+
+    /// Valor tal como va en CSS: los colores con el texto del esquema.
+    fn css_value(&self) -> String {
+        match &self.value {
+            TokenValue::Color(color) => color.to_string(),
+            TokenValue::Dimension(pixels) => format!("{}px", pixels),
+            TokenValue::FontFamily(family) => {
+                format!("\"{}\"", family.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+        }
+    }
+}
+
+/// Nombres de `AnsiColors::NAMES` en kebab-case, como en `guest-js`.
+fn ansi_token_name(name: &str) -> String {
+    match name.strip_prefix("bright") {
+        Some(color) => format!("bright-{}", color.to_ascii_lowercase()),
+        None => name.to_string(),
+    }
+}
+
+/// Colores de una variante, con `suffix` tras cada nombre.
+fn color_tokens(variant: &ThemeVariant, suffix: &str) -> Vec<Token> {
+    let ui = &variant.ui;
+    let terminal = &variant.terminal;
+    let mut tokens = vec![
+        Token::color(format!("primary{}", suffix), &ui.color.primary),
+        Token::color(format!("secondary{}", suffix), &ui.color.secondary),
+        Token::color(format!("ui-background{}", suffix), &ui.background),
+        Token::color(format!("ui-surface{}", suffix), &ui.surface),
+        Token::color(format!("ui-border{}", suffix), &ui.border),
+        Token::color(format!("text-main{}", suffix), &ui.text.main),
+        Token::color(format!("text-muted{}", suffix), &ui.text.muted),
+        Token::color(format!("text-on-primary{}", suffix), &ui.text.on_primary),
+        Token::color(format!("status-error{}", suffix), &terminal.ansi.red),
+        Token::color(format!("status-success{}", suffix), &terminal.ansi.green),
+        Token::color(format!("status-warning{}", suffix), &terminal.ansi.yellow),
+        Token::color(
+            format!("terminal-foreground{}", suffix),
+            &terminal.foreground,
+        ),
+        Token::color(
+            format!("terminal-background{}", suffix),
+            &terminal.background,
+        ),
+        Token::color(format!("terminal-cursor{}", suffix), &terminal.cursor),
+    ];
+    for (name, color) in AnsiColors::NAMES.iter().zip(terminal.ansi.colors()) {
+        tokens.push(Token::color(
+            format!("terminal-ansi-{}{}", ansi_token_name(name), suffix),
+            color,
+        ));
+    }
+    tokens
+}
+
+/// Radio de las esquinas y las fuentes no vacías de la configuración.
+fn style_tokens(config: &VSKConfig) -> Vec<Token> {
+    let mut tokens = vec![Token {
+        name: "corner-radius".to_string(),
+        value: TokenValue::Dimension(config.style.radius),
+    }];
+    for (name, family) in [
+        ("font-terminal", &config.fonts.termina),
+        ("font-title", &config.fonts.title),
+        ("font-apps", &config.fonts.apps),
+    ] {
+        if !family.trim().is_empty() {
+            tokens.push(Token {
+                name: name.to_string(),
+                value: TokenValue::FontFamily(family.trim().to_string()),
+            });
+        }
+    }
+    tokens
+}
+
+/// Todos los tokens: variante clara, variante oscura y estilo.
+fn all_tokens(config: &VSKConfig, scheme: &SchemeData) -> Vec<Token> {
+    let mut tokens = color_tokens(&scheme.colors.light, "");
+    tokens.extend(color_tokens(&scheme.colors.dark, "-dark"));
+    tokens.extend(style_tokens(config));
+    tokens
+}
+
+fn header(scheme: &SchemeData) -> String {
+    format!(
+        "Generated by Vasak from the color scheme {} and the desktop config.",
+        scheme.id
+    )
+}
+
+/// Canales en `0.0..=1.0` con cuatro decimales; bastan para 8 bits.
+fn component(value: f64) -> Value {
+    json!((value * 10_000.0).round() / 10_000.0)
+}
+
+fn dtcg_value(value: &TokenValue) -> Value {
+    match value {
+        TokenValue::Color(color) => {
+            let (red, green, blue, alpha) = color.to_rgba();
+            let [r8, g8, b8, _] = color.to_rgba8();
+            json!({
+                "colorSpace": "srgb",
+                "components": [component(red), component(green), component(blue)],
+                "alpha": component(alpha),
+                "hex": format!("#{:02x}{:02x}{:02x}", r8, g8, b8),
+            })
+        }
+        TokenValue::Dimension(pixels) => json!({ "value": pixels, "unit": "px" }),
+        TokenValue::FontFamily(family) => json!(family),
+    }
+}
+
+fn dtcg_type(value: &TokenValue) -> &'static str {
+    match value {
+        TokenValue::Color(_) => "color",
+        TokenValue::Dimension(_) => "dimension",
+        TokenValue::FontFamily(_) => "fontFamily",
+    }
+}
+
+fn render_json(scheme: &SchemeData, tokens: &[Token]) -> crate::Result<String> {
+    let mut document = Map::new();
+    document.insert("$description".to_string(), json!(header(scheme)));
+    for token in tokens {
+        document.insert(
+            token.name.clone(),
+            json!({
+                "$type": dtcg_type(&token.value),
+                "$value": dtcg_value(&token.value),
+            }),
+        );
+    }
+    let mut out =
+        serde_json::to_string_pretty(&Value::Object(document)).map_err(crate::Error::Json)?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Regla con una propiedad por token.
+fn css_rule(selector: &str, tokens: &[Token]) -> String {
+    let mut out = format!("{} {{\n", selector);
+    for token in tokens {
+        let _ = writeln!(out, "  --{}: {};", token.name, token.css_value());
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Renders the tokens of `config` and its `scheme` in `format`.
+pub fn render_tokens(
+    config: &VSKConfig,
+    scheme: &SchemeData,
+    format: TokenFormat,
+) -> crate::Result<String> {
+    let tokens = all_tokens(config, scheme);
+    match format {
+        TokenFormat::Json => render_json(scheme, &tokens),
+        TokenFormat::Css => Ok(format!(
+            "/* {} */\n\n{}",
+            header(scheme),
+            css_rule(":root", &tokens)
+        )),
+        TokenFormat::Scss => {
+            let mut out = format!("// {}\n\n", header(scheme));
+            for token in &tokens {
+                let _ = writeln!(out, "${}: {};", token.name, token.css_value());
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Stylesheet for a webview: every token in `:root`, where the unsuffixed
+/// color names hold the light values and the `-dark` ones the dark values,
+/// plus a `.dark` rule that gives the unsuffixed names their dark values, so
+/// `var(--ui-background)` follows the `dark` class on `<html>`. Without a
+/// scheme, only the corner radius and fonts.
+pub fn render_theme_css(config: &VSKConfig, scheme: Option<&SchemeData>) -> String {
+    let Some(scheme) = scheme else {
+        return css_rule(":root", &style_tokens(config));
+    };
+    format!(
+        "/* {} */\n{}{}",
+        header(scheme),
+        css_rule(":root", &all_tokens(config, scheme)),
+        css_rule(".dark", &color_tokens(&scheme.colors.dark, ""))
+    )
+}
+
+impl ConfigStore {
+    /// Renders the active scheme, in both variants, with the corner radius
+    /// and fonts of the config as design tokens in `format`.
+    pub async fn export_tokens(&self, format: TokenFormat) -> crate::Result<String> {
+        let config = self.load_config().await?;
+        let scheme_id = &config.style.color_scheme;
+        let scheme = self
+            .get_scheme_by_id(scheme_id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", scheme_id)))?;
+        render_tokens(&config, &scheme.scheme, format)
+    }
+
+    /// Stylesheet with the theme of the active config, ready to inject into
+    /// a webview; see [`render_theme_css`]. A missing scheme is not an error:
+    /// the stylesheet then carries only the corner radius and fonts.
+    pub async fn theme_css(&self) -> crate::Result<String> {
+        let config = self.load_config().await?;
+        let scheme = self.get_scheme_by_id(&config.style.color_scheme).await?;
+        Ok(render_theme_css(
+            &config,
+            scheme.as_ref().map(|scheme| &scheme.scheme),
+        ))
+    }
+
+    /// Like [`Self::theme_css`], from the calling thread; see
+    /// [`Self::load_config_blocking`].
+    pub fn theme_css_blocking(&self) -> crate::Result<String> {
+        let config = self.load_config_blocking()?;
+        self.scheme_registry().ensure_loaded_blocking()?;
+        let scheme = self.scheme_registry().get(&config.style.color_scheme);
+        Ok(render_theme_css(
+            &config,
+            scheme.as_ref().map(|scheme| &scheme.scheme),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::generate::{generate_scheme, GenerateOptions};
+
+    #[test]
+    fn theme_css_maps_unsuffixed_names_to_dark_under_dark_class() {
+        let scheme = generate_scheme(
+            &Color::parse("#3584e4").unwrap(),
+            &GenerateOptions::default(),
+        );
+        let config: VSKConfig = serde_json::from_value(serde_json::json!({
+            "style": { "darkmode": true, "color-scheme": "vasak-default", "radius": 8 },
+            "desktop": null
+        }))
+        .unwrap();
+        let css = render_theme_css(&config, Some(&scheme));
+        let light = scheme.colors.light.ui.background.as_str();
+        let dark = scheme.colors.dark.ui.background.as_str();
+
+        let (root, dark_rule) = css.split_once(".dark {").expect("no .dark rule");
+        assert!(root.contains(":root {"), "{}", css);
+        assert!(
+            root.contains(&format!("--ui-background: {};", light)),
+            "{}",
+            css
+        );
+        assert!(
+            root.contains(&format!("--ui-background-dark: {};", dark)),
+            "{}",
+            css
+        );
+        assert!(
+            dark_rule.contains(&format!("--ui-background: {};", dark)),
+            "{}",
+            css
+        );
+        assert!(!dark_rule.contains("-dark:"), "{}", css);
+    }
+}
@@ -0,0 +1,392 @@
+use std::path::{Path, PathBuf};
+
+use crate::models::{Scheme, SchemeData};
+use crate::store::ConfigStore;
+
+/// System data directories from XDG, with their defaults.
+fn system_data_dirs() -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = std::env::var_os("XDG_DATA_DIRS")
+        .map(|raw| {
+            std::env::split_paths(&raw)
+                .filter(|dir| dir.is_absolute())
+                .collect()
+        })
+        .unwrap_or_default();
+    if dirs.is_empty() {
+        vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ]
+    } else {
+        dirs
+    }
+}
+
+/// Schemes under `XDG_DATA_DIRS` belong to the package manager: they are never touched.
+pub(crate) fn is_system_path(path: &Path) -> bool {
+    system_data_dirs().iter().any(|dir| path.starts_with(dir))
+}
+
+/// Removes the `terminal` entries derived from `ui` from each variant, so a
+/// copy derives them again instead of pinning them.
+fn strip_derived(scheme: &mut serde_json::Value) {
+    for variant in ["dark", "light"] {
+        let Some(colors) = scheme
+            .pointer_mut(&format!("/colors/{}", variant))
+            .and_then(serde_json::Value::as_object_mut)
+        else {
+            continue;
+        };
+        let Some(serde_json::Value::Array(derived)) = colors.remove("derived") else {
+            continue;
+        };
+        for key in derived.iter().filter_map(serde_json::Value::as_str) {
+            let mut path: Vec<&str> = key.split('.').collect();
+            let Some(last) = path.pop() else {
+                continue;
+            };
+            let parent = path.iter().try_fold(&mut *colors, |object, segment| {
+                object.get_mut(*segment)?.as_object_mut()
+            });
+            if let Some(parent) = parent {
+                parent.remove(last);
+            }
+        }
+        // No empty objects: `terminal` may be omitted entirely.
+        if let Some(terminal) = colors
+            .get_mut("terminal")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            if terminal
+                .get("ansi")
+                .and_then(serde_json::Value::as_object)
+                .is_some_and(serde_json::Map::is_empty)
+            {
+                terminal.remove("ansi");
+            }
+            if terminal.is_empty() {
+                colors.remove("terminal");
+            }
+        }
+    }
+}
+
+/// Ids become file names, so only a conservative character set is accepted.
+fn validate_scheme_id(id: &str) -> crate::Result<()> {
+    let valid = !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(crate::Error::Other(format!(
+            "Invalid scheme id {:?}: use letters, digits, '-', '_' or '.'",
+            id
+        )))
+    }
+}
+
+/// Validates the JSON of a scheme: complete, or partial if it uses `extends`.
+fn parse_scheme_source(content: &str) -> crate::Result<(String, serde_json::Value)> {
+    let invalid = |message: String| crate::Error::Other(format!("Invalid scheme: {}", message));
+
+    let raw: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| invalid(e.to_string()))?;
+    let id = raw
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| invalid("missing field `id`".to_string()))?
+        .to_string();
+    validate_scheme_id(&id)?;
+
+    match raw.get("extends") {
+        None | Some(serde_json::Value::Null) => {
+            serde_json::from_value::<SchemeData>(raw.clone())
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+        Some(serde_json::Value::String(_)) => {}
+        Some(_) => return Err(invalid("`extends` must be a scheme id".to_string())),
+    }
+    Ok((id, raw))
+}
+
+/// Checks that the directory is writable by creating and removing a hidden file.
+async fn is_writable_dir(dir: &Path) -> bool {
+    if tokio::fs::create_dir_all(dir).await.is_err() {
+        return false;
+    }
+
+    let probe = dir.join(format!(".vasak-write-probe-{}", std::process::id()));
+    match tokio::fs::File::create(&probe).await {
+        Ok(_) => {
+            let _ = tokio::fs::remove_file(&probe).await;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+impl ConfigStore {
+    /// The first writable scheme directory outside `XDG_DATA_DIRS`, where
+    /// user schemes are installed.
+    pub async fn user_scheme_dir(&self) -> crate::Result<PathBuf> {
+        for dir in self.scheme_registry().directories()? {
+            if !is_system_path(&dir) && is_writable_dir(&dir).await {
+                return Ok(dir);
+            }
+        }
+
+        Err(crate::Error::Other(
+            "No writable scheme directory available".to_string(),
+        ))
+    }
+
+    /// Effective file for `id`, refusing system schemes.
+    async fn user_scheme_path(&self, id: &str, action: &str) -> crate::Result<PathBuf> {
+        let scheme = self
+            .get_scheme_by_id(id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", id)))?;
+        let path = PathBuf::from(&scheme.path);
+
+        if is_system_path(&path) {
+            return Err(crate::Error::Other(format!(
+                "Cannot {} system scheme {} ({}); duplicate it first",
+                action,
+                id,
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+
+    /// Writes the scheme atomically, updates the index right away and returns
+    /// the resolved scheme.
+    async fn save_scheme_file(
+        &self,
+        path: &Path,
+        id: &str,
+        scheme: &serde_json::Value,
+    ) -> crate::Result<Scheme> {
+        let content = serde_json::to_string_pretty(scheme).map_err(crate::Error::Json)?;
+        Self::write_file_atomically(path, &content).await?;
+
+        let registry = self.scheme_registry();
+        registry.refresh_paths(vec![path.to_path_buf()]).await?;
+
+        let path_text = path.to_string_lossy().to_string();
+        match registry.get(id) {
+            Some(saved) if saved.path == path_text => Ok(saved),
+            _ => {
+                let status = registry
+                    .diagnostics()
+                    .into_iter()
+                    .find(|diagnostic| diagnostic.path == path_text)
+                    .map(|diagnostic| format!("{:?}", diagnostic.status))
+                    .unwrap_or_else(|| "not indexed".to_string());
+                Err(crate::Error::Other(format!(
+                    "Scheme {} was saved to {} but is not active: {}",
+                    id, path_text, status
+                )))
+            }
+        }
+    }
+
+    /// Writes a new scheme as `<id>.json` in [`Self::user_scheme_dir`].
+    ///
+    /// A user scheme may shadow a system one with the same id, but never
+    /// replaces another user scheme.
+    async fn create_user_scheme(
+        &self,
+        id: &str,
+        scheme: serde_json::Value,
+    ) -> crate::Result<Scheme> {
+        let _guard = self.scheme_write_lock().lock().await;
+
+        if let Some(existing) = self.get_scheme_by_id(id).await? {
+            if !is_system_path(Path::new(&existing.path)) {
+                return Err(crate::Error::Other(format!(
+                    "Scheme {} already exists at {}",
+                    id, existing.path
+                )));
+            }
+        }
+
+        if let Some(parent) = scheme.get("extends").and_then(serde_json::Value::as_str) {
+            if self.get_scheme_by_id(parent).await?.is_none() {
+                return Err(crate::Error::Other(format!(
+                    "Parent scheme not found: {}",
+                    parent
+                )));
+            }
+        }
+
+        let path = self.user_scheme_dir().await?.join(format!("{}.json", id));
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Err(crate::Error::Other(format!(
+                "File already exists: {}",
+                path.display()
+            )));
+        }
+
+        self.save_scheme_file(&path, id, &scheme).await
+    }
+
+    /// Creates or replaces the user scheme `scheme.id`, shadowing a system
+    /// scheme with the same id. Used for schemes the desktop regenerates.
+    #[cfg(feature = "wallpaper")]
+    pub(crate) async fn write_user_scheme(&self, scheme: &SchemeData) -> crate::Result<Scheme> {
+        validate_scheme_id(&scheme.id)?;
+        let _guard = self.scheme_write_lock().lock().await;
+
+        let path = match self.get_scheme_by_id(&scheme.id).await? {
+            Some(existing) if !is_system_path(Path::new(&existing.path)) => {
+                PathBuf::from(existing.path)
+            }
+            _ => self
+                .user_scheme_dir()
+                .await?
+                .join(format!("{}.json", scheme.id)),
+        };
+        let value = serde_json::to_value(scheme).map_err(crate::Error::Json)?;
+        self.save_scheme_file(&path, &scheme.id, &value).await
+    }
+
+    /// Installs a scheme from a file path or from its JSON content. Partial
+    /// schemes using `extends` are kept as written. base16/base24 YAML and
+    /// `.itermcolors` files are converted and saved as JSON.
+    pub async fn install_scheme(&self, path_or_json: &str) -> crate::Result<Scheme> {
+        let is_json = path_or_json.trim_start().starts_with('{');
+        let content = if is_json {
+            path_or_json.to_string()
+        } else {
+            tokio::fs::read_to_string(path_or_json).await.map_err(|e| {
+                crate::Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Failed to read scheme file {}: {}", path_or_json, e),
+                ))
+            })?
+        };
+
+        #[cfg(feature = "scheme-import")]
+        if !is_json {
+            let path = Path::new(path_or_json);
+            if let Some(format) = crate::import::ImportFormat::from_path(path) {
+                let id = crate::import::scheme_id_for(path);
+                validate_scheme_id(&id)?;
+                let scheme = crate::import::import_scheme(&content, format, &id)?;
+                let scheme = serde_json::to_value(&scheme).map_err(crate::Error::Json)?;
+                return self.create_user_scheme(&id, scheme).await;
+            }
+        }
+
+        let (id, scheme) = parse_scheme_source(&content)?;
+        self.create_user_scheme(&id, scheme).await
+    }
+
+    /// Copies any scheme, including system ones, as a user scheme `new_id`.
+    /// The copy is fully resolved and does not extend the original, except
+    /// for the derived terminal entries, which stay derived. Like
+    /// [`Self::install_scheme`], it may shadow a system scheme but not a user
+    /// one.
+    pub async fn duplicate_scheme(&self, id: &str, new_id: &str) -> crate::Result<Scheme> {
+        validate_scheme_id(new_id)?;
+        let mut scheme = self
+            .get_scheme_by_id(id)
+            .await?
+            .ok_or_else(|| crate::Error::Other(format!("Scheme not found: {}", id)))?
+            .scheme;
+
+        scheme.id = new_id.to_string();
+        scheme.extends = None;
+        let mut scheme = serde_json::to_value(&scheme).map_err(crate::Error::Json)?;
+        strip_derived(&mut scheme);
+        self.create_user_scheme(new_id, scheme).await
+    }
+
+    /// Replaces the content of the user scheme `id`. The id cannot change.
+    pub async fn update_scheme(&self, id: &str, scheme: SchemeData) -> crate::Result<Scheme> {
+        if scheme.id != id {
+            return Err(crate::Error::Other(format!(
+                "Scheme id mismatch: expected {}, got {}",
+                id, scheme.id
+            )));
+        }
+
+        let _guard = self.scheme_write_lock().lock().await;
+        let path = self.user_scheme_path(id, "modify").await?;
+        // Imported schemes are read back from their original format: never rewrite them as JSON.
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            return Err(crate::Error::Other(format!(
+                "Cannot modify imported scheme {} ({}); duplicate it first",
+                id,
+                path.display()
+            )));
+        }
+        let scheme = serde_json::to_value(&scheme).map_err(crate::Error::Json)?;
+        self.save_scheme_file(&path, id, &scheme).await
+    }
+
+    /// Deletes the user scheme `id`. A system scheme it shadowed becomes
+    /// effective again.
+    pub async fn delete_scheme(&self, id: &str) -> crate::Result<()> {
+        let _guard = self.scheme_write_lock().lock().await;
+        let path = self.user_scheme_path(id, "delete").await?;
+
+        tokio::fs::remove_file(&path).await.map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to delete scheme file {}: {}", path.display(), e),
+            ))
+        })?;
+        self.scheme_registry().refresh_paths(vec![path]).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::generate::{generate_scheme, GenerateOptions};
+
+    #[test]
+    fn strip_derived_keeps_fixed_entries_and_derives_the_rest_again() {
+        let accent = Color::parse("#3584e4").unwrap();
+        let mut source =
+            serde_json::to_value(generate_scheme(&accent, &GenerateOptions::default())).unwrap();
+        // The dark variant only pins the cursor; the rest is derived on load.
+        let dark = source.pointer_mut("/colors/dark").unwrap();
+        let cursor = dark["terminal"]["cursor"].clone();
+        dark["terminal"] = serde_json::json!({ "cursor": cursor });
+        let loaded: SchemeData = serde_json::from_value(source).unwrap();
+        assert_eq!(loaded.colors.dark.derived.len(), 18);
+        assert!(loaded.colors.light.derived.is_empty());
+
+        let mut copy = serde_json::to_value(&loaded).unwrap();
+        strip_derived(&mut copy);
+
+        assert_eq!(
+            copy["colors"]["dark"]["terminal"],
+            serde_json::json!({ "cursor": cursor })
+        );
+        assert!(copy["colors"]["dark"].get("derived").is_none());
+        assert_eq!(
+            copy["colors"]["light"]["terminal"],
+            serde_json::to_value(&loaded.colors.light.terminal).unwrap()
+        );
+        let reloaded: SchemeData = serde_json::from_value(copy).unwrap();
+        assert_eq!(reloaded, loaded);
+    }
+
+    #[test]
+    fn validate_scheme_id_rejects_paths() {
+        assert!(validate_scheme_id("my-theme_2.dark").is_ok());
+        for id in ["", ".hidden", "../evil", "a/b", "spaced id"] {
+            assert!(validate_scheme_id(id).is_err(), "{:?}", id);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Color schemes derived from the desktop wallpaper.
+//!
+//! The image is decoded and downscaled on the CPU, its pixels are quantized
+//! to a few dominant colors in OKLab, and the most colorful of them that is
+//! also common enough becomes the seed for [`generate_scheme`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::color::{Color, ColorFormat};
+use crate::generate::{generate_scheme, GenerateOptions};
+use crate::models::{SchemeData, VSKConfig};
+use crate::store::ConfigStore;
+use crate::user_schemes::is_system_path;
+
+/// Id of the user scheme regenerated from the wallpaper.
+pub const DYNAMIC_SCHEME_ID: &str = "vasak-dynamic";
+
+/// Longest side of the thumbnail that is analyzed; more resolution does not
+/// change the result and costs much more.
+const THUMBNAIL_SIZE: u32 = 128;
+
+const CLUSTER_COUNT: usize = 8;
+const KMEANS_ITERATIONS: usize = 10;
+
+/// Options for [`ConfigStore::scheme_from_wallpaper`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WallpaperSchemeOptions {
+    /// Save the result as the user scheme [`DYNAMIC_SCHEME_ID`]. Once it
+    /// exists, [`ConfigStore::follow_wallpaper`] keeps it up to date.
+    pub save: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    lab: [f64; 3],
+    weight: f64,
+}
+
+/// One dominant color and the share of the image it covers.
+#[derive(Debug, Clone, Copy)]
+struct Cluster {
+    lab: [f64; 3],
+    share: f64,
+}
+
+impl Cluster {
+    fn chroma(&self) -> f64 {
+        self.lab[1].hypot(self.lab[2])
+    }
+
+    fn color(&self) -> Color {
+        let hue = self.lab[2].atan2(self.lab[1]).to_degrees();
+        Color::from_oklch(self.lab[0], self.chroma(), hue, 1.0).normalized(ColorFormat::Hex)
+    }
+
+    /// Vivid, common colors score higher; very dark or very light ones are
+    /// hardly perceived as the color of the image.
+    fn score(&self) -> f64 {
+        let lightness = self.lab[0];
+        let visibility = if (0.25..=0.9).contains(&lightness) {
+            1.0
+        } else {
+            0.5
+        };
+        self.share.sqrt() * (self.chroma().min(0.2) / 0.2) * visibility
+    }
+}
+
+fn to_oklab(color: &Color) -> [f64; 3] {
+    let (lightness, chroma, hue) = color.to_oklch();
+    let hue = hue.to_radians();
+    [lightness, chroma * hue.cos(), chroma * hue.sin()]
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+/// Groups the pixels in buckets of 5 bits per channel; each bucket is
+/// analyzed once, with its mean color and its pixel count as weight.
+fn load_samples(path: &Path) -> crate::Result<Vec<Sample>> {
+    let image_error = |e: &dyn std::fmt::Display| {
+        crate::Error::Other(format!("Failed to decode image {}: {}", path.display(), e))
+    };
+
+    let image = image::ImageReader::open(path)
+        .map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read image {}: {}", path.display(), e),
+            ))
+        })?
+        .with_guessed_format()
+        .map_err(|e| image_error(&e))?
+        .decode()
+        .map_err(|e| image_error(&e))?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgba8();
+
+    let mut buckets: HashMap<u32, (u64, [u64; 3])> = HashMap::new();
+    for pixel in image.pixels() {
+        let [red, green, blue, alpha] = pixel.0;
+        if alpha < 128 {
+            continue;
+        }
+        let key = (u32::from(red >> 3) << 10) | (u32::from(green >> 3) << 5) | u32::from(blue >> 3);
+        let bucket = buckets.entry(key).or_insert((0, [0; 3]));
+        bucket.0 += 1;
+        bucket.1[0] += u64::from(red);
+        bucket.1[1] += u64::from(green);
+        bucket.1[2] += u64::from(blue);
+    }
+
+    if buckets.is_empty() {
+        return Err(crate::Error::Other(format!(
+            "Image {} has no opaque pixels",
+            path.display()
+        )));
+    }
+
+    let mut samples: Vec<Sample> = buckets
+        .into_values()
+        .map(|(count, sums)| {
+            let mean = |sum: u64| sum as f64 / count as f64 / 255.0;
+            let color = Color::from_rgba(mean(sums[0]), mean(sums[1]), mean(sums[2]), 1.0);
+            Sample {
+                lab: to_oklab(&color),
+                weight: count as f64,
+            }
+        })
+        .collect();
+    // Stable order, so the same image always gives the same result.
+    samples.sort_by(|a, b| {
+        b.weight
+            .total_cmp(&a.weight)
+            .then_with(|| a.lab[0].total_cmp(&b.lab[0]))
+    });
+    Ok(samples)
+}
+
+/// Weighted k-means in OKLab, seeded with the most populated bucket and then
+/// with the ones farthest from the centers already chosen.
+fn quantize(samples: &[Sample], count: usize) -> Vec<Cluster> {
+    let mut centers = vec![samples[0].lab];
+    while centers.len() < count.min(samples.len()) {
+        let next = samples
+            .iter()
+            .map(|sample| {
+                let nearest = centers
+                    .iter()
+                    .map(|center| distance(&sample.lab, center))
+                    .fold(f64::INFINITY, f64::min);
+                (sample, sample.weight * nearest)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match next {
+            Some((sample, weight)) if weight > 0.0 => centers.push(sample.lab),
+            _ => break,
+        }
+    }
+
+    let total: f64 = samples.iter().map(|sample| sample.weight).sum();
+    let mut weights = vec![0.0; centers.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![[0.0; 3]; centers.len()];
+        weights = vec![0.0; centers.len()];
+        for sample in samples {
+            let (index, _) = centers
+                .iter()
+                .enumerate()
+                .map(|(index, center)| (index, distance(&sample.lab, center)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("at least one center");
+            for (sum, value) in sums[index].iter_mut().zip(sample.lab) {
+                *sum += value * sample.weight;
+            }
+            weights[index] += sample.weight;
+        }
+        for ((center, sum), weight) in centers.iter_mut().zip(&sums).zip(&weights) {
+            if *weight > 0.0 {
+                *center = sum.map(|value| value / weight);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = centers
+        .into_iter()
+        .zip(weights)
+        .filter(|(_, weight)| *weight > 0.0)
+        .map(|(lab, weight)| Cluster {
+            lab,
+            share: weight / total,
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.share.total_cmp(&a.share));
+    clusters
+}
+
+/// Picks the best scored color; an image without color gives its dominant gray.
+fn pick_seed(clusters: &[Cluster]) -> Color {
+    clusters
+        .iter()
+        .filter(|cluster| cluster.chroma() >= 0.03)
+        .max_by(|a, b| a.score().total_cmp(&b.score()))
+        .unwrap_or(&clusters[0])
+        .color()
+}
+
+/// Dominant colors of the image at `path`, most common first.
+pub fn dominant_colors(path: &Path, count: usize) -> crate::Result<Vec<Color>> {
+    let samples = load_samples(path)?;
+    Ok(quantize(&samples, count.max(1))
+        .iter()
+        .map(Cluster::color)
+        .collect())
+}
+
+/// The accent color a scheme for the image at `path` is built from.
+pub fn seed_color(path: &Path) -> crate::Result<Color> {
+    let samples = load_samples(path)?;
+    Ok(pick_seed(&quantize(&samples, CLUSTER_COUNT)))
+}
+
+/// Wallpapers may be stored as `file://` URIs.
+fn wallpaper_path(config: &VSKConfig) -> Option<PathBuf> {
+    let wallpaper = config.desktop.as_ref()?.wallpaper.first()?;
+    let path = wallpaper.strip_prefix("file://").unwrap_or(wallpaper);
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The description records the source wallpaper, so every process can tell
+/// whether the dynamic scheme already matches the current one.
+fn dynamic_description(path: &Path) -> String {
+    format!("Generated from the wallpaper {}", path.display())
+}
+
+impl ConfigStore {
+    /// The first wallpaper of the config, if any.
+    pub async fn current_wallpaper(&self) -> crate::Result<Option<PathBuf>> {
+        Ok(wallpaper_path(&self.load_config().await?))
+    }
+
+    /// Builds a scheme from the image at `path`, or from the current
+    /// wallpaper when `path` is `None`. The scheme id is
+    /// [`DYNAMIC_SCHEME_ID`]; with `options.save` it is also written as that
+    /// user scheme.
+    pub async fn scheme_from_wallpaper(
+        &self,
+        path: Option<&str>,
+        options: WallpaperSchemeOptions,
+    ) -> crate::Result<SchemeData> {
+        let path = match path {
+            Some(path) => PathBuf::from(path.strip_prefix("file://").unwrap_or(path)),
+            None => self
+                .current_wallpaper()
+                .await?
+                .ok_or_else(|| crate::Error::Other("No wallpaper configured".to_string()))?,
+        };
+
+        let seed_path = path.clone();
+        let seed = tokio::task::spawn_blocking(move || seed_color(&seed_path))
+            .await
+            .map_err(|e| crate::Error::Other(format!("Wallpaper analysis failed: {}", e)))??;
+
+        let scheme = generate_scheme(
+            &seed,
+            &GenerateOptions {
+                id: Some(DYNAMIC_SCHEME_ID.to_string()),
+                name: Some("Vasak Dynamic".to_string()),
+                author: None,
+                description: Some(dynamic_description(&path)),
+            },
+        );
+
+        if options.save {
+            self.write_user_scheme(&scheme).await?;
+        }
+        Ok(scheme)
+    }
+
+    /// Regenerates the user scheme [`DYNAMIC_SCHEME_ID`] each time the
+    /// wallpaper in the config changes. Does nothing until that scheme has
+    /// been saved once with [`Self::scheme_from_wallpaper`]. Never returns,
+    /// like [`Self::follow_theme_changes`]: spawn it on the runtime and abort
+    /// the task to stop it.
+    pub async fn follow_wallpaper(&self) {
+        let mut changes = self.subscribe_changes();
+        let mut last = self.current_wallpaper().await.ok().flatten();
+
+        loop {
+            let config = match changes.recv().await {
+                Ok(config) => config,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    match self.load_config().await {
+                        Ok(config) => config,
+                        Err(_) => continue,
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            };
+
+            let wallpaper = wallpaper_path(&config);
+            if wallpaper == last {
+                continue;
+            }
+            last = wallpaper.clone();
+            let Some(path) = wallpaper else {
+                continue;
+            };
+
+            let up_to_date = match self.get_scheme_by_id(DYNAMIC_SCHEME_ID).await {
+                Ok(Some(existing)) => {
+                    is_system_path(Path::new(&existing.path))
+                        || existing.scheme.description == dynamic_description(&path)
+                }
+                _ => true,
+            };
+            if up_to_date {
+                continue;
+            }
+
+            let path_text = path.to_string_lossy().to_string();
+            let options = WallpaperSchemeOptions { save: true };
+            if let Err(e) = self.scheme_from_wallpaper(Some(&path_text), options).await {
+                eprintln!(
+                    "[ConfigStore] Failed to regenerate {} from {}: {}",
+                    DYNAMIC_SCHEME_ID, path_text, e
+                );
+            }
+        }
+    }
+}
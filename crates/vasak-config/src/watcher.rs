@@ -0,0 +1,114 @@
+use notify::{EventKind, RecommendedWatcher, Watcher};
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::store::ConfigStore;
+
+/// Keeps a file watcher alive; dropping it stops the notifications.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+fn should_handle_event(event: &notify::Event, watched_file_path: &Path) -> bool {
+    let is_relevant_kind = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+
+    is_relevant_kind && event.paths.iter().any(|path| path == watched_file_path)
+}
+
+fn watch_config_file<F>(
+    watched_file_path: std::path::PathBuf,
+    on_change: F,
+) -> impl FnMut(notify::Result<notify::Event>) + Send + 'static
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let debounce_window = Duration::from_millis(250);
+    let on_change = Arc::new(on_change);
+    let pending = Arc::new(AtomicBool::new(false));
+    move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            if let Err(e) = res {
+                eprintln!(
+                    "[Config Watcher Callback] Error watching config file: {:?}",
+                    e
+                );
+            }
+            return;
+        };
+
+        if should_handle_event(&event, watched_file_path.as_path()) {
+            // Debounce por flanco final: la recarga se hace al terminar la
+            // ventana, así una edición externa justo después de una escritura
+            // propia no se pierde.
+            if pending.swap(true, Ordering::AcqRel) {
+                return;
+            }
+
+            let pending = pending.clone();
+            let on_change = on_change.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(debounce_window);
+                pending.store(false, Ordering::Release);
+                on_change();
+            });
+        }
+    }
+}
+
+impl ConfigStore {
+    /// Watches the config file and calls `on_change` (debounced) when it is
+    /// created or modified.
+    ///
+    /// The parent directory is watched instead of the file itself: atomic
+    /// writes replace the file through a rename, which would leave a watch on
+    /// the old inode without further events.
+    pub fn watch<F>(&self, on_change: F) -> crate::Result<ConfigWatcher>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let config_path = self.config_path()?;
+        let watch_target = config_path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .ok_or_else(|| {
+                crate::Error::Other(format!(
+                    "Invalid config path without parent: {}",
+                    config_path.display()
+                ))
+            })?;
+
+        std::fs::create_dir_all(&watch_target).map_err(|e| {
+            crate::Error::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to create config directory {}: {}",
+                    watch_target.display(),
+                    e
+                ),
+            ))
+        })?;
+
+        let mut watcher = notify::recommended_watcher(watch_config_file(config_path, on_change))
+            .map_err(|e| {
+                crate::Error::Other(format!("Cannot create watcher for config file: {}", e))
+            })?;
+
+        watcher
+            .watch(watch_target.as_path(), notify::RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                crate::Error::Other(format!(
+                    "Failed to watch config path {}: {}",
+                    watch_target.display(),
+                    e
+                ))
+            })?;
+
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
@@ -0,0 +1,145 @@
+//! `org.vasak.Config` against a private `dbus-daemon --session`.
+//!
+//! The store, the service and the client all read the process environment,
+//! so every check lives in a single test.
+
+#![cfg(feature = "dbus")]
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use vasak_config::dbus::{DbusService, DBUS_OBJECT_PATH, DBUS_SERVICE_NAME};
+use vasak_config::{generate_scheme, Color, ConfigStore, GenerateOptions, VSKConfig};
+
+/// Private session bus, killed when dropped.
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    /// `None` when `dbus-daemon` is not installed.
+    fn start() -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take()?)
+            .read_line(&mut address)
+            .ok()?;
+        Some(Self {
+            daemon,
+            address: address.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+async fn call<R>(
+    proxy: &zbus::Proxy<'_>,
+    method: &str,
+    args: &(impl serde::Serialize + zbus::zvariant::DynamicType),
+) -> R
+where
+    R: for<'d> zbus::zvariant::DynamicDeserialize<'d>,
+{
+    proxy
+        .call(method, args)
+        .await
+        .unwrap_or_else(|e| panic!("{} failed: {}", method, e))
+}
+
+#[tokio::test]
+async fn serves_the_config_on_a_private_bus() {
+    let Some(bus) = PrivateBus::start() else {
+        eprintln!("dbus-daemon not found; skipping");
+        return;
+    };
+
+    let home = tempfile::tempdir().unwrap();
+    let schemes = home.path().join("schemes");
+    std::fs::create_dir_all(&schemes).unwrap();
+    let accent = Color::parse("#3584e4").unwrap();
+    let scheme = generate_scheme(
+        &accent,
+        &GenerateOptions {
+            id: Some("test-blue".to_string()),
+            ..Default::default()
+        },
+    );
+    std::fs::write(
+        schemes.join("test-blue.json"),
+        serde_json::to_string_pretty(&scheme).unwrap(),
+    )
+    .unwrap();
+
+    std::env::set_var("HOME", home.path());
+    std::env::set_var("XDG_CONFIG_HOME", home.path().join("config"));
+    std::env::set_var("VASAK_CONFIG_PATH", home.path().join("vasak.conf"));
+    std::env::set_var("VASAK_SCHEMES_PATHS", &schemes);
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &bus.address);
+    // Sin gsettings en el PATH el modo oscuro no toca el escritorio real.
+    std::env::set_var("PATH", home.path());
+
+    let store = ConfigStore::new();
+    let _service = DbusService::start(store.clone()).await.unwrap();
+
+    let client = zbus::Connection::session().await.unwrap();
+    let proxy = zbus::Proxy::new(
+        &client,
+        DBUS_SERVICE_NAME,
+        DBUS_OBJECT_PATH,
+        "org.vasak.Config",
+    )
+    .await
+    .unwrap();
+    let mut changed = proxy.receive_signal("Changed").await.unwrap();
+
+    let darkmode: String = call(&proxy, "Get", &("/style/darkmode",)).await;
+    assert_eq!(darkmode, "false");
+
+    let () = call(&proxy, "Set", &("/style/radius", "12")).await;
+    let radius: String = call(&proxy, "Get", &("/style/radius",)).await;
+    assert_eq!(radius, "12");
+    assert_eq!(store.load_config().await.unwrap().style.radius, 12);
+
+    let signal = tokio::time::timeout(Duration::from_secs(5), changed.next())
+        .await
+        .expect("no Changed signal after Set")
+        .unwrap();
+    let (payload,): (String,) = signal.body().deserialize().unwrap();
+    let config: VSKConfig = serde_json::from_str(&payload).unwrap();
+    assert_eq!(config.style.radius, 12);
+
+    let () = call(&proxy, "SetDarkMode", &(true,)).await;
+    assert!(store.load_config().await.unwrap().style.darkmode);
+    let signal = tokio::time::timeout(Duration::from_secs(5), changed.next())
+        .await
+        .expect("no Changed signal after SetDarkMode")
+        .unwrap();
+    let (payload,): (String,) = signal.body().deserialize().unwrap();
+    let config: VSKConfig = serde_json::from_str(&payload).unwrap();
+    assert!(config.style.darkmode);
+
+    let listed: String = call(&proxy, "GetSchemes", &()).await;
+    let listed: Vec<serde_json::Value> = serde_json::from_str(&listed).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["scheme"]["id"], "test-blue");
+
+    let missing = proxy
+        .call_method("Get", &("/style/missing",))
+        .await
+        .unwrap_err();
+    assert!(missing.to_string().contains("InvalidArgs"), "{}", missing);
+}
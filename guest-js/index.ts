@@ -1,15 +1,54 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { defineStore } from "pinia";
 import { ref } from "vue";
 
-export async function writeConfig(value: VSKConfig): Promise<void> {
+export async function writeConfig(
+  value: VSKConfig,
+  options?: ConfigEventOptions,
+): Promise<void> {
   await invoke("plugin:config-manager|write_config", {
     payload: JSON.stringify(value),
+    options,
   });
 }
 
-export async function setDarkMode(darkmode: boolean): Promise<void> {
-  await invoke("plugin:config-manager|set_darkmode", { darkmode });
+export async function setDarkMode(
+  darkmode: boolean,
+  options?: ConfigEventOptions,
+): Promise<void> {
+  await invoke("plugin:config-manager|set_darkmode", { darkmode, options });
+}
+
+export async function onConfigChanged(
+  handler: (payload: ConfigChangedPayload) => void,
+  { ignoreOwn = true }: { ignoreOwn?: boolean } = {},
+): Promise<UnlistenFn> {
+  const ownLabel = getCurrentWebview().label;
+  return await listen<ConfigChangedPayload>("config-changed", (event) => {
+    if (ignoreOwn && event.payload?.origin === ownLabel) {
+      return;
+    }
+    handler(event.payload);
+  });
+}
+
+export async function onSchemesChanged(
+  handler: (payload: SchemesChangedPayload) => void,
+): Promise<UnlistenFn> {
+  return await listen<SchemesChangedPayload>("schemes-changed", (event) =>
+    handler(event.payload),
+  );
+}
+
+export async function onActiveSchemeChanged(
+  handler: (payload: ActiveSchemeChangedPayload) => void,
+): Promise<UnlistenFn> {
+  return await listen<ActiveSchemeChangedPayload>(
+    "active-scheme-changed",
+    (event) => handler(event.payload),
+  );
 }
 
 export async function readConfig(): Promise<VSKConfig | null> {
@@ -26,14 +65,185 @@ export async function readConfig(): Promise<VSKConfig | null> {
   return null;
 }
 
-export async function getSchemes(): Promise<Scheme[]> {
-  return await invoke<Scheme[]>("plugin:config-manager|get_schemes");
+export async function getSchemes(
+  options?: SchemeOptions,
+): Promise<Scheme[]> {
+  return await invoke<Scheme[]>("plugin:config-manager|get_schemes", {
+    colorFormat: options?.colorFormat,
+  });
+}
+
+export async function getRawScheme(
+  schemeId: string,
+): Promise<RawScheme | null> {
+  return await invoke<RawScheme | null>(
+    "plugin:config-manager|get_raw_scheme",
+    { schemeId },
+  );
+}
+
+export async function checkSchemeContrast(
+  schemeId: string,
+  options?: ContrastOptions,
+): Promise<ContrastReport> {
+  return await invoke<ContrastReport>(
+    "plugin:config-manager|check_scheme_contrast",
+    { schemeId, options },
+  );
+}
+
+export async function generateScheme(
+  accent: string,
+  options?: GenerateOptions,
+): Promise<SchemeData> {
+  return await invoke<SchemeData>("plugin:config-manager|generate_scheme", {
+    accent,
+    options,
+  });
+}
+
+export async function schemeFromWallpaper(
+  path?: string,
+  options?: WallpaperSchemeOptions,
+): Promise<SchemeData> {
+  return await invoke<SchemeData>(
+    "plugin:config-manager|scheme_from_wallpaper",
+    { path, options },
+  );
+}
+
+export async function exportScheme(
+  schemeId: string,
+  format: ExportFormat,
+  variant?: SchemeVariant,
+): Promise<string> {
+  return await invoke<string>("plugin:config-manager|export_scheme", {
+    schemeId,
+    format,
+    variant,
+  });
+}
+
+export async function applyTerminalTheme(
+  formats?: ExportFormat[],
+): Promise<AppliedTheme[]> {
+  return await invoke<AppliedTheme[]>(
+    "plugin:config-manager|apply_terminal_theme",
+    { formats },
+  );
+}
+
+export async function applyGtkTheme(): Promise<AppliedTheme[]> {
+  return await invoke<AppliedTheme[]>("plugin:config-manager|apply_gtk_theme");
+}
+
+export async function applyQtTheme(): Promise<AppliedTheme[]> {
+  return await invoke<AppliedTheme[]>("plugin:config-manager|apply_qt_theme");
+}
+
+export async function applyX11Theme(): Promise<AppliedTheme[]> {
+  return await invoke<AppliedTheme[]>("plugin:config-manager|apply_x11_theme");
+}
+
+export async function exportTokens(format: TokenFormat): Promise<string> {
+  return await invoke<string>("plugin:config-manager|export_tokens", {
+    format,
+  });
+}
+
+export async function getThemeCss(): Promise<string> {
+  return await invoke<string>("plugin:config-manager|get_theme_css");
+}
+
+export async function installScheme(source: string): Promise<Scheme> {
+  return await invoke<Scheme>("plugin:config-manager|install_scheme", {
+    source,
+  });
+}
+
+export async function duplicateScheme(
+  schemeId: string,
+  newId: string,
+): Promise<Scheme> {
+  return await invoke<Scheme>("plugin:config-manager|duplicate_scheme", {
+    schemeId,
+    newId,
+  });
+}
+
+export async function updateScheme(
+  schemeId: string,
+  scheme: SchemeData,
+): Promise<Scheme> {
+  return await invoke<Scheme>("plugin:config-manager|update_scheme", {
+    schemeId,
+    scheme,
+  });
+}
+
+export async function deleteScheme(schemeId: string): Promise<void> {
+  await invoke("plugin:config-manager|delete_scheme", { schemeId });
 }
 
-export async function getSchemeById(schemeId: string): Promise<Scheme | null> {
-  return await invoke<Scheme | null>("plugin:config-manager|get_scheme_by_id", { schemeId });
+export async function getSchemeDiagnostics(): Promise<SchemeDiagnostic[]> {
+  return await invoke<SchemeDiagnostic[]>(
+    "plugin:config-manager|get_scheme_diagnostics",
+  );
+}
+
+export async function getSchemeById(
+  schemeId: string,
+  options?: SchemeOptions,
+): Promise<Scheme | null> {
+  return await invoke<Scheme | null>("plugin:config-manager|get_scheme_by_id", {
+    schemeId,
+    colorFormat: options?.colorFormat,
+  });
 }
 
+export async function subscribeConfig(
+  onUpdate: (update: ConfigUpdate) => void,
+): Promise<() => Promise<void>> {
+  const channel = new Channel<ConfigUpdate>();
+  channel.onmessage = onUpdate;
+  const subscriptionId = await invoke<number>(
+    "plugin:config-manager|subscribe_config",
+    { onUpdate: channel },
+  );
+
+  return async () => {
+    await invoke("plugin:config-manager|unsubscribe_config", { subscriptionId });
+  };
+}
+
+export type EmitTarget =
+  | { kind: "all" }
+  | { kind: "exceptOrigin" }
+  | { kind: "labels"; labels: string[] };
+
+export type ConfigEventOptions = {
+  target?: EmitTarget;
+};
+
+export type ConfigChangedPayload = {
+  origin: string | null;
+};
+
+export type SchemesChangedPayload = {
+  added: string[];
+  removed: string[];
+  modified: string[];
+};
+
+export type ActiveSchemeChangedPayload = {
+  id: string;
+  scheme: Scheme | null;
+};
+
+export type ConfigUpdate =
+  | { kind: "snapshot"; sequence: number; config: VSKConfig }
+  | { kind: "changed"; sequence: number; config: VSKConfig; changes: string[] };
+
 export type VSKConfig = {
   style: {
     darkmode: boolean;
@@ -62,15 +272,126 @@ export type Scheme = {
   scheme: SchemeData;
 };
 
+export type SchemeStatus =
+  | { status: "loaded"; id: string }
+  | { status: "shadowed"; id: string; shadowedBy: string }
+  | { status: "invalidJson"; message: string; line: number; column: number }
+  | {
+      status: "missingField";
+      field: string;
+      message: string;
+      line: number;
+      column: number;
+    }
+  | { status: "invalidScheme"; message: string; line: number; column: number }
+  | { status: "unreadable"; message: string }
+  | { status: "missingParent"; id: string; parent: string }
+  | { status: "invalidParent"; id: string; parent: string }
+  | { status: "inheritanceCycle"; id: string; chain: string[] }
+  | { status: "hidden" }
+  | { status: "nonUtf8Name" };
+
+export type SchemeDiagnostic = { path: string } & SchemeStatus;
+
 export type SchemeData = {
   id: string;
   name: string;
   author: string;
   description: string;
   version: string;
+  extends?: string;
   colors: SchemeColors;
 };
 
+export type ColorFormat = "hex" | "rgb" | "oklch";
+
+export type SchemeOptions = {
+  /** Rewrite every color in this notation; by default colors are returned as written. */
+  colorFormat?: ColorFormat;
+};
+
+export type GenerateOptions = {
+  /** Defaults to `generated-<hex>`. */
+  id?: string;
+  name?: string;
+  author?: string;
+  description?: string;
+};
+
+export type WallpaperSchemeOptions = {
+  /** Save the result as the `vasak-dynamic` user scheme and keep it in sync with the wallpaper. */
+  save?: boolean;
+};
+
+export type SchemeVariant = "dark" | "light";
+
+export type ExportFormat =
+  | "alacritty"
+  | "kitty"
+  | "foot"
+  | "wezterm"
+  | "gnome-terminal"
+  | "gtk3"
+  | "gtk4"
+  | "kde"
+  | "qt5ct"
+  | "qt6ct"
+  | "vscode"
+  | "neovim"
+  | "base16"
+  | "base24"
+  | "itermcolors"
+  | "xresources"
+  | "xsettingsd";
+
+export type TokenFormat = "json" | "css" | "scss";
+
+export type AppliedTheme = {
+  format: ExportFormat;
+  /** File written, or `dconf:<path>` for GNOME Terminal. */
+  target: string;
+  /** Why this format could not be applied; the others are still applied. */
+  error?: string;
+};
+
+export type ContrastOptions = {
+  /** Also compute the APCA lightness contrast (Lc) of each pair. */
+  apca?: boolean;
+};
+
+export type WcagVerdict = {
+  aa: boolean;
+  aaLarge: boolean;
+  aaa: boolean;
+  aaaLarge: boolean;
+};
+
+export type ContrastPair = {
+  variant: SchemeVariant;
+  /** Role of the foreground, e.g. `ui.text.main`. */
+  foregroundRole: string;
+  backgroundRole: string;
+  foreground: string;
+  background: string;
+  /** WCAG contrast ratio, from 1 to 21. */
+  ratio: number;
+  wcag: WcagVerdict;
+  /** APCA Lc, only present when requested. Negative for light text on dark backgrounds. */
+  apca?: number;
+};
+
+export type ContrastReport = {
+  schemeId: string;
+  /** Whether every pair reaches AA for normal text. */
+  passesAa: boolean;
+  pairs: ContrastPair[];
+};
+
+export type RawScheme = {
+  path: string;
+  scheme: Partial<SchemeData> & Record<string, unknown>;
+};
+
 export type SchemeColors = {
   dark: ThemeVariant;
   light: ThemeVariant;
@@ -78,7 +399,10 @@ export type SchemeColors = {
 
 export type ThemeVariant = {
   ui: UiColors;
+  /** Always complete; entries missing from the scheme file are derived from `ui`. */
   terminal: TerminalColors;
+  /** Derived terminal entries, e.g. `terminal.ansi.brightRed`. Remove an entry when setting its color by hand. */
+  derived?: string[];
 };
 
 export type UiColors = {
@@ -126,6 +450,19 @@ export type AnsiColors = {
   brightWhite: string;
 };
 
+const THEME_STYLE_ID = "vasak-theme";
+
+/** Inserts the stylesheet from `getThemeCss()` in `<head>`, or replaces the previous one. */
+export function applyThemeCss(css: string): void {
+  let style = document.getElementById(THEME_STYLE_ID);
+  if (style === null) {
+    style = document.createElement("style");
+    style.id = THEME_STYLE_ID;
+    document.head.appendChild(style);
+  }
+  style.textContent = css;
+}
+
 let configStore: ReturnType<
   typeof defineStore<
     "config",
@@ -156,270 +493,7 @@ export const useConfigStore = () => {
 
     const setProperties = async () => {
       if (config.value?.style) {
-        const { "color-scheme": colorScheme, radius } = config.value.style;
-        const scheme = await getSchemeById(colorScheme);
-
-        if (scheme !== null && scheme !== undefined) {
-          const darkScheme = scheme.scheme.colors.dark;
-          const lightScheme = scheme.scheme.colors.light;
-
-          // Colores de Marca
-          document.documentElement.style.setProperty(
-            "--primary",
-            lightScheme.ui.color.primary,
-          );
-          document.documentElement.style.setProperty(
-            "--secondary",
-            lightScheme.ui.color.secondary,
-          );
-          document.documentElement.style.setProperty(
-            "--primary-dark",
-            darkScheme.ui.color.primary,
-          );
-          document.documentElement.style.setProperty(
-            "--secondary-dark",
-            darkScheme.ui.color.secondary,
-          );
-
-          // Colores de Interfaz (UI)
-          document.documentElement.style.setProperty(
-            "--ui-background",
-            lightScheme.ui.background,
-          );
-          document.documentElement.style.setProperty(
-            "--ui-surface",
-            lightScheme.ui.surface,
-          );
-          document.documentElement.style.setProperty(
-            "--ui-border",
-            lightScheme.ui.border,
-          );
-          document.documentElement.style.setProperty(
-            "--ui-background-dark",
-            darkScheme.ui.background,
-          );
-          document.documentElement.style.setProperty(
-            "--ui-surface-dark",
-            darkScheme.ui.surface,
-          );
-          document.documentElement.style.setProperty(
-            "--ui-border-dark",
-            darkScheme.ui.border,
-          );
-
-          // Colores de Texto
-          document.documentElement.style.setProperty(
-            "--text-main",
-            lightScheme.ui.text.main,
-          );
-          document.documentElement.style.setProperty(
-            "--text-muted",
-            lightScheme.ui.text.muted,
-          );
-          document.documentElement.style.setProperty(
-            "--text-on-primary",
-            lightScheme.ui.text["on-primary"],
-          );
-          document.documentElement.style.setProperty(
-            "--text-main-dark",
-            darkScheme.ui.text.main,
-          );
-          document.documentElement.style.setProperty(
-            "--text-muted-dark",
-            darkScheme.ui.text.muted,
-          );
-          document.documentElement.style.setProperty(
-            "--text-on-primary-dark",
-            darkScheme.ui.text["on-primary"],
-          );
-
-          // Status Colors
-          document.documentElement.style.setProperty(
-            "--status-error",
-            lightScheme.terminal.ansi.red,
-          );
-          document.documentElement.style.setProperty(
-            "--status-success",
-            lightScheme.terminal.ansi.green,
-          );
-          document.documentElement.style.setProperty(
-            "--status-warning",
-            lightScheme.terminal.ansi.yellow,
-          );
-          document.documentElement.style.setProperty(
-            "--status-error-dark",
-            darkScheme.terminal.ansi.red,
-          );
-          document.documentElement.style.setProperty(
-            "--status-success-dark",
-            darkScheme.terminal.ansi.green,
-          );
-          document.documentElement.style.setProperty(
-            "--status-warning-dark",
-            darkScheme.terminal.ansi.yellow,
-          );
-
-          // Terminal Colors
-          document.documentElement.style.setProperty(
-            "--terminal-foreground",
-            lightScheme.terminal.foreground,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-background",
-            lightScheme.terminal.background,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-cursor",
-            lightScheme.terminal.cursor,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-foreground-dark",
-            darkScheme.terminal.foreground,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-background-dark",
-            darkScheme.terminal.background,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-cursor-dark",
-            darkScheme.terminal.cursor,
-          );
-
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-black",
-            lightScheme.terminal.ansi.black,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-red",
-            lightScheme.terminal.ansi.red,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-green",
-            lightScheme.terminal.ansi.green,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-yellow",
-            lightScheme.terminal.ansi.yellow,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-blue",
-            lightScheme.terminal.ansi.blue,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-magenta",
-            lightScheme.terminal.ansi.magenta,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-cyan",
-            lightScheme.terminal.ansi.cyan,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-white",
-            lightScheme.terminal.ansi.white,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-black",
-            lightScheme.terminal.ansi.brightBlack,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-red",
-            lightScheme.terminal.ansi.brightRed,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-green",
-            lightScheme.terminal.ansi.brightGreen,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-yellow",
-            lightScheme.terminal.ansi.brightYellow,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-blue",
-            lightScheme.terminal.ansi.brightBlue,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-magenta",
-            lightScheme.terminal.ansi.brightMagenta,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-cyan",
-            lightScheme.terminal.ansi.brightCyan,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-white",
-            lightScheme.terminal.ansi.brightWhite,
-          );
-
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-black-dark",
-            darkScheme.terminal.ansi.black,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-red-dark",
-            darkScheme.terminal.ansi.red,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-green-dark",
-            darkScheme.terminal.ansi.green,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-yellow-dark",
-            darkScheme.terminal.ansi.yellow,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-blue-dark",
-            darkScheme.terminal.ansi.blue,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-magenta-dark",
-            darkScheme.terminal.ansi.magenta,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-cyan-dark",
-            darkScheme.terminal.ansi.cyan,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-white-dark",
-            darkScheme.terminal.ansi.white,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-black-dark",
-            darkScheme.terminal.ansi.brightBlack,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-red-dark",
-            darkScheme.terminal.ansi.brightRed,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-green-dark",
-            darkScheme.terminal.ansi.brightGreen,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-yellow-dark",
-            darkScheme.terminal.ansi.brightYellow,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-blue-dark",
-            darkScheme.terminal.ansi.brightBlue,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-magenta-dark",
-            darkScheme.terminal.ansi.brightMagenta,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-cyan-dark",
-            darkScheme.terminal.ansi.brightCyan,
-          );
-          document.documentElement.style.setProperty(
-            "--terminal-ansi-bright-white-dark",
-            darkScheme.terminal.ansi.brightWhite,
-          );
-        }
-
-        document.documentElement.style.setProperty(
-          "--corner-radius",
-          `${radius}px`,
-        );
+        applyThemeCss(await getThemeCss());
       }
     };
 
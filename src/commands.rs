@@ -1,12 +1,29 @@
-use tauri::{command, AppHandle, Runtime};
+use tauri::{command, ipc::Channel, AppHandle, Runtime, Webview};
 
-use crate::models::Scheme;
+use crate::events::ConfigEventOptions;
+use crate::models::{Scheme, SchemeData, SchemeVariant};
+use crate::subscriptions::ConfigUpdate;
 use crate::ConfigManagerExt;
 use crate::Result;
+use crate::{
+    AppliedTheme, Color, ColorFormat, ContrastOptions, ContrastReport, Error, ExportFormat,
+    GenerateOptions, RawScheme, SchemeDiagnostic, TokenFormat,
+};
 
 #[command]
-pub(crate) async fn write_config<R: Runtime>(app: AppHandle<R>, payload: String) -> Result<()> {
-    app.config_manager().write_config(&payload).await
+pub(crate) async fn write_config<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: String,
+    options: Option<ConfigEventOptions>,
+) -> Result<()> {
+    app.config_manager()
+        .write_config_from(
+            &payload,
+            Some(webview.label()),
+            &options.unwrap_or_default(),
+        )
+        .await
 }
 
 // remember to call `.manage(MyState::default())`
@@ -16,16 +33,198 @@ pub async fn read_config<R: Runtime>(app: AppHandle<R>) -> Result<String> {
 }
 
 #[command]
-pub async fn set_darkmode<R: Runtime>(app: AppHandle<R>, darkmode: bool) -> Result<()> {
-    app.config_manager().set_darkmode(darkmode).await
+pub async fn set_darkmode<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    darkmode: bool,
+    options: Option<ConfigEventOptions>,
+) -> Result<()> {
+    app.config_manager()
+        .set_darkmode_from(
+            darkmode,
+            Some(webview.label()),
+            &options.unwrap_or_default(),
+        )
+        .await
 }
 
 #[command]
-pub async fn get_schemes<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Scheme>> {
-    app.config_manager().load_schemes().await
+pub async fn get_schemes<R: Runtime>(
+    app: AppHandle<R>,
+    color_format: Option<ColorFormat>,
+) -> Result<Vec<Scheme>> {
+    let schemes = app.config_manager().load_schemes().await?;
+    Ok(match color_format {
+        Some(format) => schemes
+            .into_iter()
+            .map(|scheme| scheme.normalized(format))
+            .collect(),
+        None => schemes,
+    })
 }
 
 #[command]
-pub async fn get_scheme_by_id<R: Runtime>(app: AppHandle<R>, scheme_id: String) -> Result<Option<Scheme>> {
-    app.config_manager().get_scheme_by_id(&scheme_id).await
+pub async fn get_scheme_by_id<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+    color_format: Option<ColorFormat>,
+) -> Result<Option<Scheme>> {
+    let scheme = app.config_manager().get_scheme_by_id(&scheme_id).await?;
+    Ok(match color_format {
+        Some(format) => scheme.map(|scheme| scheme.normalized(format)),
+        None => scheme,
+    })
+}
+
+#[command]
+pub async fn get_scheme_diagnostics<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<SchemeDiagnostic>> {
+    app.config_manager().get_scheme_diagnostics().await
+}
+
+#[command]
+pub async fn get_raw_scheme<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+) -> Result<Option<RawScheme>> {
+    app.config_manager().get_raw_scheme(&scheme_id).await
+}
+
+#[command]
+pub async fn install_scheme<R: Runtime>(app: AppHandle<R>, source: String) -> Result<Scheme> {
+    app.config_manager().install_scheme(&source).await
+}
+
+#[command]
+pub async fn duplicate_scheme<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+    new_id: String,
+) -> Result<Scheme> {
+    app.config_manager()
+        .duplicate_scheme(&scheme_id, &new_id)
+        .await
+}
+
+#[command]
+pub async fn update_scheme<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+    scheme: SchemeData,
+) -> Result<Scheme> {
+    app.config_manager().update_scheme(&scheme_id, scheme).await
+}
+
+#[command]
+pub async fn delete_scheme<R: Runtime>(app: AppHandle<R>, scheme_id: String) -> Result<()> {
+    app.config_manager().delete_scheme(&scheme_id).await
+}
+
+#[command]
+pub async fn check_scheme_contrast<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+    options: Option<ContrastOptions>,
+) -> Result<ContrastReport> {
+    app.config_manager()
+        .check_scheme_contrast(&scheme_id, options.unwrap_or_default())
+        .await
+}
+
+#[command]
+pub async fn generate_scheme(
+    accent: String,
+    options: Option<GenerateOptions>,
+) -> Result<SchemeData> {
+    let accent = Color::parse(&accent).map_err(|e| Error::Other(e.to_string()))?;
+    Ok(vasak_config::generate_scheme(
+        &accent,
+        &options.unwrap_or_default(),
+    ))
+}
+
+#[cfg(feature = "wallpaper")]
+#[command]
+pub async fn scheme_from_wallpaper<R: Runtime>(
+    app: AppHandle<R>,
+    path: Option<String>,
+    options: Option<vasak_config::WallpaperSchemeOptions>,
+) -> Result<SchemeData> {
+    app.config_manager()
+        .scheme_from_wallpaper(path.as_deref(), options.unwrap_or_default())
+        .await
+}
+
+#[cfg(not(feature = "wallpaper"))]
+#[command]
+pub async fn scheme_from_wallpaper() -> Result<SchemeData> {
+    Err(Error::Other(
+        "scheme_from_wallpaper needs the wallpaper feature".to_string(),
+    ))
+}
+
+#[command]
+pub async fn export_scheme<R: Runtime>(
+    app: AppHandle<R>,
+    scheme_id: String,
+    format: ExportFormat,
+    variant: Option<SchemeVariant>,
+) -> Result<String> {
+    app.config_manager()
+        .export_scheme(&scheme_id, variant, format)
+        .await
+}
+
+#[command]
+pub async fn apply_terminal_theme<R: Runtime>(
+    app: AppHandle<R>,
+    formats: Option<Vec<ExportFormat>>,
+) -> Result<Vec<AppliedTheme>> {
+    app.config_manager().apply_terminal_theme(formats).await
+}
+
+#[command]
+pub async fn apply_gtk_theme<R: Runtime>(app: AppHandle<R>) -> Result<Vec<AppliedTheme>> {
+    app.config_manager().apply_gtk_theme().await
+}
+
+#[command]
+pub async fn apply_qt_theme<R: Runtime>(app: AppHandle<R>) -> Result<Vec<AppliedTheme>> {
+    app.config_manager().apply_qt_theme().await
+}
+
+#[command]
+pub async fn apply_x11_theme<R: Runtime>(app: AppHandle<R>) -> Result<Vec<AppliedTheme>> {
+    app.config_manager().apply_x11_theme().await
+}
+
+#[command]
+pub async fn export_tokens<R: Runtime>(app: AppHandle<R>, format: TokenFormat) -> Result<String> {
+    app.config_manager().export_tokens(format).await
+}
+
+#[command]
+pub async fn get_theme_css<R: Runtime>(app: AppHandle<R>) -> Result<String> {
+    app.config_manager().get_theme_css().await
+}
+
+#[command]
+pub async fn subscribe_config<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    on_update: Channel<ConfigUpdate>,
+) -> Result<u32> {
+    let window_label = webview.window().label().to_string();
+    app.config_manager()
+        .subscribe(webview.label(), &window_label, on_update)
+        .await
+}
+
+#[command]
+pub async fn unsubscribe_config<R: Runtime>(
+    app: AppHandle<R>,
+    subscription_id: u32,
+) -> Result<bool> {
+    Ok(app.config_manager().unsubscribe(subscription_id))
 }
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, EventTarget, Runtime};
+
+use crate::models::Scheme;
+
+/// Payload of the `config-changed` event.
+///
+/// `origin` is the label of the webview whose command caused the change, or
+/// `None` when the file was modified outside the app.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigChangedPayload {
+    pub origin: Option<String>,
+}
+
+/// Payload of the `active-scheme-changed` event.
+///
+/// `scheme` is the new effective scheme for `id`, or `None` if no file
+/// provides it anymore.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSchemeChangedPayload {
+    pub id: String,
+    pub scheme: Option<Scheme>,
+}
+
+/// Which windows receive a `config-changed` event.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EmitTarget {
+    /// Every window, including the one that made the change.
+    #[default]
+    All,
+    /// Every window except the origin.
+    ExceptOrigin,
+    /// Only the windows or webviews with these labels.
+    Labels { labels: Vec<String> },
+}
+
+/// Options accepted by the commands that modify the config.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigEventOptions {
+    #[serde(default)]
+    pub target: EmitTarget,
+}
+
+fn target_label(target: &EventTarget) -> Option<&str> {
+    match target {
+        EventTarget::AnyLabel { label }
+        | EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label } => Some(label.as_str()),
+        _ => None,
+    }
+}
+
+/// Emits `config-changed` honoring the requested target.
+///
+/// Listeners registered without a target (global `listen` in JS) receive every
+/// event regardless of the filter, so they should compare `origin` with their
+/// own label.
+pub(crate) fn emit_config_changed<R: Runtime>(
+    app: &AppHandle<R>,
+    origin: Option<&str>,
+    options: &ConfigEventOptions,
+) -> tauri::Result<()> {
+    let payload = ConfigChangedPayload {
+        origin: origin.map(str::to_string),
+    };
+
+    match (&options.target, origin) {
+        (EmitTarget::ExceptOrigin, Some(origin)) => {
+            app.emit_filter(crate::CONFIG_CHANGED_EVENT, payload, |target| {
+                target_label(target) != Some(origin)
+            })
+        }
+        (EmitTarget::Labels { labels }, _) => {
+            app.emit_filter(crate::CONFIG_CHANGED_EVENT, payload, |target| {
+                target_label(target)
+                    .map(|label| labels.iter().any(|l| l == label))
+                    .unwrap_or(false)
+            })
+        }
+        _ => app.emit(crate::CONFIG_CHANGED_EVENT, payload),
+    }
+}
@@ -1,27 +1,40 @@
-use notify::{EventKind, RecommendedWatcher, Watcher};
-use std::{
-    path::Path,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
-};
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tauri::{
-    plugin::{Builder, TauriPlugin},
-    Emitter, Manager, Runtime,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    Emitter, Manager, RunEvent, Runtime, WindowEvent,
 };
 
 mod commands;
 #[cfg(desktop)]
 mod desktop;
-mod error;
-mod models;
+mod events;
+mod subscriptions;
+mod theme_script;
+mod window_theme;
 
-pub use error::{Error, Result};
-pub use models::*;
+pub use events::{
+    ActiveSchemeChangedPayload, ConfigChangedPayload, ConfigEventOptions, EmitTarget,
+};
+pub use subscriptions::ConfigUpdate;
+pub use vasak_config::{
+    color, contrast, export, generate, models, models::*, tokens, AppliedTheme, Color, ColorFormat,
+    ColorParseError, ConfigStore, ConfigWatcher, ContrastOptions, ContrastPair, ContrastReport,
+    Error, ExportFormat, GenerateOptions, RawScheme, Result, SchemeChanges, SchemeDiagnostic,
+    SchemeRegistry, SchemeStatus, SchemeWatcher, ShadowedScheme, TokenFormat, WcagVerdict,
+};
+#[cfg(feature = "scheme-import")]
+pub use vasak_config::{import, import_scheme, ImportFormat};
+#[cfg(feature = "wallpaper")]
+pub use vasak_config::{wallpaper, WallpaperSchemeOptions, DYNAMIC_SCHEME_ID};
+pub use window_theme::WindowSync;
 
 #[cfg(desktop)]
 use desktop::ConfigManager;
 
 pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+pub const SCHEMES_CHANGED_EVENT: &str = "schemes-changed";
+pub const ACTIVE_SCHEME_CHANGED_EVENT: &str = "active-scheme-changed";
 
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the config-manager APIs.
 pub trait ConfigManagerExt<R: Runtime> {
@@ -34,125 +47,277 @@ impl<R: Runtime, T: Manager<R>> crate::ConfigManagerExt<R> for T {
     }
 }
 
-fn should_handle_event(event: &notify::Event, watched_file_path: &Path) -> bool {
-    let is_relevant_kind = matches!(
-        event.kind,
-        EventKind::Create(_) | EventKind::Modify(_)
-    );
-
-    is_relevant_kind && event.paths.iter().any(|path| path == watched_file_path)
-}
-
-fn watch_config_file<R: Runtime + 'static>(
-    app: &tauri::AppHandle<R>,
-    watched_file_path: std::path::PathBuf,
-) -> Box<dyn FnMut(notify::Result<notify::Event>) + Send + 'static> {
-    let app_handle = app.clone();
-    let debounce_window = Duration::from_millis(250);
-    let last_refresh = Arc::new(Mutex::new(None::<Instant>));
-    Box::new(move |res: notify::Result<notify::Event>| {
-        let Ok(event) = res else {
-            if let Err(e) = res {
+fn on_config_file_changed<R: Runtime>(app: &tauri::AppHandle<R>) {
+    // Refrescar el caché del plugin leyendo de disco y luego emitir el evento.
+    let app_for_async = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Obtener el estado del ConfigManager y actualizar su cache.
+        let state = app_for_async.state::<desktop::ConfigManager<R>>();
+        let changed = match state.inner().reload_from_file().await {
+            Ok(changed) => changed,
+            Err(e) => {
                 eprintln!(
-                    "[Config Watcher Callback] Error watching config file: {:?}",
+                    "[Config Watcher Callback] Failed to refresh config cache: {}",
                     e
                 );
+                true
             }
-            return;
         };
+        // Las escrituras del propio plugin ya emitieron su evento con origen.
+        if !changed {
+            return;
+        }
+        // Emitir evento para frontends
+        events::emit_config_changed(&app_for_async, None, &ConfigEventOptions::default())
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "[Config Watcher Callback] Failed to emit config-changed event: {}",
+                    e
+                );
+            });
+    });
+}
 
-        if should_handle_event(&event, watched_file_path.as_path()) {
-            let lock_state = last_refresh.lock();
-            let Ok(mut last_refresh_at) = lock_state else {
-                eprintln!("[Config Watcher Callback] Debounce mutex poisoned");
-                return;
-            };
+fn on_schemes_changed<R: Runtime>(app: &tauri::AppHandle<R>, changes: &SchemeChanges) {
+    if let Err(e) = app.emit(SCHEMES_CHANGED_EVENT, changes) {
+        eprintln!(
+            "[Scheme Watcher Callback] Failed to emit schemes-changed event: {}",
+            e
+        );
+    }
 
-            if last_refresh_at
-                .as_ref()
-                .map(|at| at.elapsed() < debounce_window)
-                .unwrap_or(false)
-            {
+    // Avisar aparte si cambió el archivo que respalda el esquema activo.
+    let app_for_async = app.clone();
+    let changes = changes.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_for_async.state::<desktop::ConfigManager<R>>();
+        let store = state.inner().store();
+        let active_id = match store.load_config().await {
+            Ok(config) => config.style.color_scheme,
+            Err(e) => {
+                eprintln!(
+                    "[Scheme Watcher Callback] Cannot read the active scheme: {}",
+                    e
+                );
                 return;
             }
+        };
+        if !changes.affects(&active_id) {
+            return;
+        }
 
-            *last_refresh_at = Some(Instant::now());
-
-            // Refrescar el caché del plugin leyendo de disco y luego emitir el evento.
-            let app_for_async = app_handle.clone();
-            tauri::async_runtime::spawn(async move {
-                // Obtener el estado del ConfigManager y actualizar su cache.
-                let state = app_for_async.state::<desktop::ConfigManager<R>>();
-                if let Err(e) = state.inner().refresh_cache_from_file().await {
-                    eprintln!(
-                        "[Config Watcher Callback] Failed to refresh config cache: {}",
-                        e
-                    );
-                }
-                // Emitir evento para frontends
-                app_for_async
-                    .emit(CONFIG_CHANGED_EVENT, ())
-                    .unwrap_or_else(|e| {
+        match store.sync_applied_themes().await {
+            Ok(applied) => {
+                for theme in applied {
+                    if let Some(error) = theme.error {
                         eprintln!(
-                            "[Config Watcher Callback] Failed to emit config-changed event: {}",
-                            e
+                            "[Scheme Watcher Callback] Could not apply the {} theme: {}",
+                            theme.format.as_str(),
+                            error
                         );
-                    });
-            });
+                    }
+                }
+            }
+            Err(e) => eprintln!(
+                "[Scheme Watcher Callback] Failed to sync applied themes: {}",
+                e
+            ),
         }
-    })
+
+        theme_script::reinject(&app_for_async, store).await;
+        window_theme::apply(&app_for_async, store, None).await;
+
+        let payload = ActiveSchemeChangedPayload {
+            scheme: store.scheme_registry().get(&active_id),
+            id: active_id,
+        };
+        if let Err(e) = app_for_async.emit(ACTIVE_SCHEME_CHANGED_EVENT, payload) {
+            eprintln!(
+                "[Scheme Watcher Callback] Failed to emit active-scheme-changed event: {}",
+                e
+            );
+        }
+    });
 }
 
-/// Initializes the plugin.
+#[cfg(feature = "dbus")]
+fn start_dbus_service<R: Runtime>(app: &tauri::AppHandle<R>, store: ConfigStore) {
+    let app_for_listener = app.clone();
+    let listener: vasak_config::dbus::RemoteChangeListener =
+        std::sync::Arc::new(move |config: &VSKConfig| {
+            if let Some(config_manager) = app_for_listener.try_state::<ConfigManager<R>>() {
+                config_manager.inner().notify_external_change(config);
+            }
+        });
+
+    let app_for_async = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Solo un proceso puede ser dueño del nombre; el resto sigue sin servicio.
+        match vasak_config::dbus::DbusService::start_with_listener(store, Some(listener)).await {
+            Ok(service) => {
+                app_for_async.manage(service);
+            }
+            Err(e) => eprintln!(
+                "[ConfigManager] Could not publish {} on the session bus: {}",
+                vasak_config::dbus::DBUS_SERVICE_NAME,
+                e
+            ),
+        }
+    });
+}
+
+/// Initializes the plugin with the default options; see [`Builder`].
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("config-manager")
+    Builder::new().build()
+}
+
+/// Builds the plugin with options that [`init`] leaves off.
+#[derive(Debug, Default)]
+pub struct Builder {
+    synced_windows: HashMap<String, WindowSync>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the native theme of the window `label` (titlebar, scrollbars,
+    /// native dialogs) follow dark mode, and with `options.background` its
+    /// background follow the active scheme. Off by default for every window.
+    pub fn sync_window(mut self, label: impl Into<String>, options: WindowSync) -> Self {
+        self.synced_windows.insert(label.into(), options);
+        self
+    }
+
+    /// Builds the plugin.
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        build_plugin(self.synced_windows)
+    }
+}
+
+/// Reads the config once to build the script that themes each webview
+/// before its first paint, and registers the commands and watchers. Each
+/// page is themed again as it loads, with the latest theme.
+fn build_plugin<R: Runtime>(synced_windows: HashMap<String, WindowSync>) -> TauriPlugin<R> {
+    let store = ConfigStore::new();
+    let mut builder = PluginBuilder::new("config-manager");
+    let init_script = theme_script::init_script(&store);
+    if let Some(script) = &init_script {
+        builder = builder.js_init_script(script.clone());
+    }
+
+    builder
         .invoke_handler(tauri::generate_handler![
             commands::read_config,
             commands::write_config,
             commands::set_darkmode,
             commands::get_schemes,
-            commands::get_scheme_by_id
+            commands::get_scheme_by_id,
+            commands::subscribe_config,
+            commands::unsubscribe_config,
+            commands::get_scheme_diagnostics,
+            commands::install_scheme,
+            commands::duplicate_scheme,
+            commands::update_scheme,
+            commands::delete_scheme,
+            commands::get_raw_scheme,
+            commands::check_scheme_contrast,
+            commands::generate_scheme,
+            commands::scheme_from_wallpaper,
+            commands::export_scheme,
+            commands::apply_terminal_theme,
+            commands::apply_gtk_theme,
+            commands::apply_qt_theme,
+            commands::apply_x11_theme,
+            commands::export_tokens,
+            commands::get_theme_css
         ])
-        .setup(|app, api| {
-            let config_manager = desktop::init(app, api)?;
-            let config_path = config_manager.config_path()?;
-            app.manage(config_manager);
-
-            let watch_target = if config_path.exists() {
-                config_path.clone()
-            } else {
-                config_path
-                    .parent()
-                    .map(std::path::Path::to_path_buf)
-                    .ok_or_else(|| {
-                        Error::Other(format!(
-                            "Invalid config path without parent: {}",
-                            config_path.display()
-                        ))
-                    })?
-            };
+        .setup(move |app, api| {
+            let config_manager = desktop::init(app, api, store)?;
 
             let app_handle_for_watcher = app.clone();
-            let event_handler = watch_config_file(&app_handle_for_watcher, config_path.clone());
-
-            let mut watcher: RecommendedWatcher = notify::recommended_watcher(event_handler)
-                .map_err(|e| {
-                    Error::Other(format!("Cannot create watcher for config file: {}", e))
-                })?;
-
-            watcher
-                .watch(watch_target.as_path(), notify::RecursiveMode::NonRecursive)
-                .map_err(|e| {
-                    Error::Other(format!(
-                        "Failed to watch config path {}: {}",
-                        watch_target.display(),
-                        e
-                    ))
-                })?;
+            let watcher = config_manager
+                .store()
+                .watch(move || on_config_file_changed(&app_handle_for_watcher))?;
+            let app_handle_for_schemes = app.clone();
+            let scheme_watcher = config_manager.store().watch_schemes(move |changes| {
+                on_schemes_changed(&app_handle_for_schemes, changes)
+            })?;
+
+            #[cfg(feature = "dbus")]
+            start_dbus_service(app, config_manager.store().clone());
+
+            // Reescribe los temas aplicados al cambiar el modo oscuro o el esquema activo.
+            let themed_store = config_manager.store().clone();
+            tauri::async_runtime::spawn(async move { themed_store.follow_theme_changes().await });
 
+            // Los webviews abiertos siguen al modo y al esquema sin recargar.
+            let app_for_theme = app.clone();
+            let theme_store = config_manager.store().clone();
+            tauri::async_runtime::spawn(theme_script::follow_config(app_for_theme, theme_store));
+
+            // Solo las ventanas que la app registró con `Builder::sync_window`.
+            if !synced_windows.is_empty() {
+                app.manage(window_theme::SyncedWindows(synced_windows));
+                let app_for_windows = app.clone();
+                let window_store = config_manager.store().clone();
+                tauri::async_runtime::spawn(window_theme::follow_config(
+                    app_for_windows,
+                    window_store,
+                ));
+            }
+
+            // Mantiene `vasak-dynamic` al día con el fondo, si el usuario lo guardó.
+            #[cfg(feature = "wallpaper")]
+            {
+                let store = config_manager.store().clone();
+                tauri::async_runtime::spawn(async move { store.follow_wallpaper().await });
+            }
+
+            app.manage(config_manager);
+            app.manage(theme_script::ThemedWebviews::<R>::new(init_script));
             app.manage(Mutex::new(watcher));
+            app.manage(Mutex::new(scheme_watcher));
 
             Ok(())
         })
+        .on_webview_ready(|webview| {
+            // El tema de la página llega con `on_page_load`; aquí solo se
+            // registra el webview y se aplica el de la ventana nativa.
+            let app = webview.app_handle().clone();
+            let Some(config_manager) = app.try_state::<ConfigManager<R>>() else {
+                return;
+            };
+            let store = config_manager.inner().store().clone();
+            if let Some(webviews) = app.try_state::<theme_script::ThemedWebviews<R>>() {
+                webviews.insert(webview.clone());
+            }
+            let label = webview.window_ref().label().to_string();
+            tauri::async_runtime::spawn(async move {
+                window_theme::apply(&app, &store, Some(&label)).await;
+            });
+        })
+        .on_page_load(|webview, _payload| {
+            // Recargas y navegaciones vuelven a correr el script de inicio,
+            // que lleva el tema del arranque: se aplica el último.
+            theme_script::on_page_load(webview);
+        })
+        .on_event(|app, event| {
+            // Liberar los canales de suscripción de las ventanas destruidas.
+            if let RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::Destroyed,
+                ..
+            } = event
+            {
+                if let Some(config_manager) = app.try_state::<ConfigManager<R>>() {
+                    config_manager.inner().unsubscribe_label(label);
+                }
+                if let Some(webviews) = app.try_state::<theme_script::ThemedWebviews<R>>() {
+                    webviews.remove_window(label);
+                }
+            }
+        })
         .build()
 }
@@ -0,0 +1,317 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::ipc::Channel;
+
+use crate::models::VSKConfig;
+
+/// Message pushed through a `subscribe_config` channel.
+///
+/// The first message of every subscription is a `snapshot`; each following
+/// message is a `changed` update carrying the new config and the JSON
+/// pointers that differ from the previous one.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ConfigUpdate {
+    Snapshot {
+        sequence: u64,
+        config: VSKConfig,
+    },
+    Changed {
+        sequence: u64,
+        config: VSKConfig,
+        changes: Vec<String>,
+    },
+}
+
+struct Subscription {
+    webview: String,
+    window: String,
+    channel: Channel<ConfigUpdate>,
+    sequence: u64,
+}
+
+/// Registry of the channels subscribed to config updates.
+#[derive(Default)]
+pub(crate) struct ConfigSubscriptions {
+    next_id: u32,
+    entries: HashMap<u32, Subscription>,
+    last: Option<Value>,
+    /// Cuenta cada `publish` que cambió `last`.
+    generation: u64,
+}
+
+impl ConfigSubscriptions {
+    /// Number of published changes; read it before reading the config that
+    /// is passed to [`Self::subscribe`].
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Registers a channel and sends it the latest config as a snapshot.
+    ///
+    /// `current` is the config read when [`Self::generation`] was
+    /// `read_at`. If nothing was published since, it is the newest value and
+    /// is published first, so earlier subscribers do not miss it; otherwise
+    /// the value published in between is newer and becomes the snapshot.
+    pub(crate) fn subscribe(
+        &mut self,
+        webview: &str,
+        window: &str,
+        channel: Channel<ConfigUpdate>,
+        current: Value,
+        read_at: u64,
+    ) -> crate::Result<u32> {
+        let config: VSKConfig = serde_json::from_value(current).map_err(crate::Error::Json)?;
+        if self.generation == read_at {
+            self.publish(&config);
+        }
+        let snapshot = match self.last.clone() {
+            Some(last) => serde_json::from_value(last).map_err(crate::Error::Json)?,
+            None => config,
+        };
+
+        channel
+            .send(ConfigUpdate::Snapshot {
+                sequence: 0,
+                config: snapshot,
+            })
+            .map_err(|e| crate::Error::Other(format!("Failed to send config snapshot: {}", e)))?;
+
+        self.next_id = self.next_id.wrapping_add(1);
+        let id = self.next_id;
+        self.entries.insert(
+            id,
+            Subscription {
+                webview: webview.to_string(),
+                window: window.to_string(),
+                channel,
+                sequence: 0,
+            },
+        );
+
+        Ok(id)
+    }
+
+    pub(crate) fn unsubscribe(&mut self, id: u32) -> bool {
+        self.entries.remove(&id).is_some()
+    }
+
+    /// Drops every subscription owned by the given window or webview label.
+    pub(crate) fn unsubscribe_label(&mut self, label: &str) {
+        self.entries
+            .retain(|_, sub| sub.window != label && sub.webview != label);
+    }
+
+    /// Pushes `config` to every subscriber if it differs from the last published value.
+    pub(crate) fn publish(&mut self, config: &VSKConfig) {
+        let Ok(value) = serde_json::to_value(config) else {
+            return;
+        };
+
+        let mut changes = Vec::new();
+        match self.last.as_ref() {
+            Some(previous) => diff_values(previous, &value, String::new(), &mut changes),
+            None => changes.push(String::new()),
+        }
+
+        if changes.is_empty() {
+            return;
+        }
+
+        self.last = Some(value);
+        self.generation += 1;
+
+        // Los canales que fallan pertenecen a webviews que ya no existen.
+        self.entries.retain(|id, sub| {
+            sub.sequence += 1;
+            let update = ConfigUpdate::Changed {
+                sequence: sub.sequence,
+                config: config.clone(),
+                changes: changes.clone(),
+            };
+            match sub.channel.send(update) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!(
+                        "[ConfigManager::subscriptions] Dropping subscription {} for webview {}: {}",
+                        id, sub.webview, e
+                    );
+                    false
+                }
+            }
+        });
+    }
+}
+
+/// Collects the JSON pointers of every leaf that differs between `old` and `new`.
+fn diff_values(old: &Value, new: &Value, pointer: String, changes: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, new_value) in new_map {
+                let child = format!("{}/{}", pointer, escape_pointer_token(key));
+                match old_map.get(key) {
+                    Some(old_value) => diff_values(old_value, new_value, child, changes),
+                    None => changes.push(child),
+                }
+            }
+            for key in old_map.keys() {
+                if !new_map.contains_key(key) {
+                    changes.push(format!("{}/{}", pointer, escape_pointer_token(key)));
+                }
+            }
+        }
+        _ if old != new => changes.push(pointer),
+        _ => {}
+    }
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tauri::ipc::InvokeResponseBody;
+
+    fn changes(old: Value, new: Value) -> Vec<String> {
+        let mut changes = Vec::new();
+        diff_values(&old, &new, String::new(), &mut changes);
+        changes
+    }
+
+    fn config(darkmode: bool, radius: u32) -> Value {
+        json!({
+            "style": { "darkmode": darkmode, "color-scheme": "vasak-default", "radius": radius },
+            "desktop": null,
+            "fonts": { "termina": "", "title": "", "apps": "" },
+            "icons": { "dark": "", "light": "" }
+        })
+    }
+
+    /// Canal que guarda cada mensaje como JSON.
+    fn recording_channel() -> (Channel<ConfigUpdate>, Arc<Mutex<Vec<Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let channel = Channel::new(move |body| {
+            if let InvokeResponseBody::Json(json) = body {
+                sink.lock()
+                    .unwrap()
+                    .push(serde_json::from_str(&json).unwrap());
+            }
+            Ok(())
+        });
+        (channel, received)
+    }
+
+    #[test]
+    fn diff_lists_the_pointer_of_each_changed_leaf() {
+        assert_eq!(
+            changes(config(false, 8), config(true, 12)),
+            vec!["/style/darkmode", "/style/radius"]
+        );
+        assert!(changes(config(false, 8), config(false, 8)).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_keys() {
+        assert_eq!(
+            changes(json!({ "a": 1, "b": 2 }), json!({ "a": 1, "c": 3 })),
+            vec!["/c", "/b"]
+        );
+        assert_eq!(
+            changes(
+                json!({ "desktop": null }),
+                json!({ "desktop": { "iconsize": 48 } })
+            ),
+            vec!["/desktop"]
+        );
+    }
+
+    #[test]
+    fn diff_treats_arrays_as_leaves_and_escapes_keys() {
+        assert_eq!(
+            changes(
+                json!({ "w": ["a.png"] }),
+                json!({ "w": ["a.png", "b.png"] })
+            ),
+            vec!["/w"]
+        );
+        assert_eq!(
+            changes(
+                json!({ "a/b": { "c~d": 1 } }),
+                json!({ "a/b": { "c~d": 2 } })
+            ),
+            vec!["/a~1b/c~0d"]
+        );
+        assert_eq!(changes(json!(1), json!(2)), vec![""]);
+    }
+
+    #[test]
+    fn snapshot_is_the_config_just_read() {
+        let mut subscriptions = ConfigSubscriptions::default();
+        let (first, _) = recording_channel();
+        subscriptions
+            .subscribe("main", "main", first, config(false, 8), 0)
+            .unwrap();
+
+        let (second, received) = recording_channel();
+        let read_at = subscriptions.generation();
+        subscriptions
+            .subscribe("other", "other", second, config(true, 8), read_at)
+            .unwrap();
+        let snapshot = &received.lock().unwrap()[0];
+        assert_eq!(snapshot["kind"], "snapshot");
+        assert_eq!(snapshot["config"]["style"]["darkmode"], true);
+    }
+
+    #[test]
+    fn snapshot_is_not_older_than_a_publish_after_the_read() {
+        let mut subscriptions = ConfigSubscriptions::default();
+        let (first, first_received) = recording_channel();
+        subscriptions
+            .subscribe("main", "main", first, config(false, 8), 0)
+            .unwrap();
+
+        // Se publica un cambio entre la lectura y la suscripción.
+        let read_at = subscriptions.generation();
+        let published: VSKConfig = serde_json::from_value(config(true, 8)).unwrap();
+        subscriptions.publish(&published);
+        let (second, received) = recording_channel();
+        subscriptions
+            .subscribe("other", "other", second, config(false, 8), read_at)
+            .unwrap();
+
+        assert_eq!(
+            received.lock().unwrap()[0]["config"]["style"]["darkmode"],
+            true
+        );
+        // `last` sigue siendo lo publicado: volver a publicarlo no es un cambio.
+        subscriptions.publish(&published);
+        assert_eq!(first_received.lock().unwrap().len(), 2);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn updates_carry_the_changes_since_the_snapshot() {
+        let mut subscriptions = ConfigSubscriptions::default();
+        let (channel, received) = recording_channel();
+        subscriptions
+            .subscribe("main", "main", channel, config(false, 8), 0)
+            .unwrap();
+
+        let same: VSKConfig = serde_json::from_value(config(false, 8)).unwrap();
+        subscriptions.publish(&same);
+        let changed: VSKConfig = serde_json::from_value(config(false, 12)).unwrap();
+        subscriptions.publish(&changed);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1]["kind"], "changed");
+        assert_eq!(received[1]["sequence"], 1);
+        assert_eq!(received[1]["changes"], json!(["/style/radius"]));
+    }
+}
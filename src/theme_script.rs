@@ -0,0 +1,250 @@
+//! Theme of the webviews before their first paint.
+//!
+//! The plugin registers an initialization script, built from the config when
+//! the plugin is created, that sets the `dark` class on `<html>` and inserts
+//! the stylesheet of [`ConfigStore::theme_css`]. Every page that starts
+//! loading afterwards (a new webview, a reload, a navigation) also gets the
+//! latest theme, and later changes are evaluated again in every open
+//! webview. Each script carries a generation, so the startup script never
+//! undoes a newer one in the same document.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, Webview};
+use vasak_config::{ConfigStore, VSKConfig};
+
+/// Id del `<style>`; `applyThemeCss()` de `guest-js` reemplaza el mismo.
+const THEME_STYLE_ID: &str = "vasak-theme";
+
+/// Script idempotente: sirve como script de inicialización y para `eval`.
+/// No hace nada si el documento ya tiene un tema de una generación posterior.
+fn theme_script(darkmode: bool, css: &str, generation: u64) -> String {
+    // Un string JSON es un literal de JavaScript válido.
+    let css = serde_json::Value::String(css.to_string());
+    format!(
+        r#"(function () {{
+  if ((window.__vasakThemeGeneration || 0) > {generation}) return;
+  window.__vasakThemeGeneration = {generation};
+  var apply = function () {{
+    var root = document.documentElement;
+    if (!root) return false;
+    root.classList.toggle("dark", {darkmode});
+    var style = document.getElementById("{id}");
+    if (!style) {{
+      style = document.createElement("style");
+      style.id = "{id}";
+      (document.head || root).appendChild(style);
+    }}
+    style.textContent = {css};
+    return true;
+  }};
+  // Al inicio del documento puede no existir aún `<html>`.
+  if (!apply()) {{
+    new MutationObserver(function (_, observer) {{
+      if (apply()) observer.disconnect();
+    }}).observe(document, {{ childList: true }});
+  }}
+}})();"#,
+        generation = generation,
+        darkmode = darkmode,
+        id = THEME_STYLE_ID,
+        css = css,
+    )
+}
+
+/// Script for the webviews created from now on, with the theme at startup
+/// (generation 0). `None` when there is no config yet: pages are then themed
+/// as they load.
+///
+/// `init` runs before the Tauri runtime exists, so the config and the
+/// schemes are read from the calling thread; this also warms the caches of
+/// `store`.
+pub(crate) fn init_script(store: &ConfigStore) -> Option<String> {
+    let script = store.load_config_blocking().and_then(|config| {
+        let css = store.theme_css_blocking()?;
+        Ok(theme_script(config.style.darkmode, &css, 0))
+    });
+
+    match script {
+        Ok(script) => Some(script),
+        Err(e) => {
+            eprintln!("[ConfigManager] Cannot build the theme init script: {}", e);
+            None
+        }
+    }
+}
+
+/// Lo que decide el tema de los webviews; el resto de la configuración
+/// (fondo de pantalla, escritorio) no obliga a reinyectar.
+fn theme_inputs(config: &VSKConfig) -> impl PartialEq {
+    (
+        config.style.color_scheme.clone(),
+        config.style.darkmode,
+        config.style.radius,
+        config.fonts.clone(),
+    )
+}
+
+/// Every webview created since startup, child webviews included, by label,
+/// and the latest theme script; `Manager::webviews` needs Tauri's `unstable`
+/// feature.
+pub(crate) struct ThemedWebviews<R: Runtime> {
+    webviews: Mutex<HashMap<String, Webview<R>>>,
+    script: Mutex<Option<(u64, String)>>,
+    generation: AtomicU64,
+}
+
+impl<R: Runtime> ThemedWebviews<R> {
+    /// Starts from the init script, if there is one.
+    pub(crate) fn new(init_script: Option<String>) -> Self {
+        Self {
+            webviews: Mutex::new(HashMap::new()),
+            script: Mutex::new(init_script.map(|script| (0, script))),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn insert(&self, webview: Webview<R>) {
+        self.webviews
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(webview.label().to_string(), webview);
+    }
+
+    /// Forgets the webviews hosted by the destroyed window `window`.
+    pub(crate) fn remove_window(&self, window: &str) {
+        self.webviews
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|_, webview| webview.window_ref().label() != window);
+    }
+
+    fn all(&self) -> Vec<Webview<R>> {
+        self.webviews
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// The latest script built, if any.
+    fn current(&self) -> Option<String> {
+        self.script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .map(|(_, script)| script.clone())
+    }
+
+    /// Builds the script of the current theme, with a new generation, and
+    /// keeps it for the pages that load later unless a newer one is kept.
+    async fn refresh(&self, store: &ConfigStore) -> Option<String> {
+        // La generación se toma antes de leer: la lectura posterior es la más nueva.
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let built = async {
+            let config = store.load_config().await?;
+            let css = store.theme_css().await?;
+            crate::Result::Ok(theme_script(config.style.darkmode, &css, generation))
+        }
+        .await;
+        let script = match built {
+            Ok(script) => script,
+            Err(e) => {
+                eprintln!("[ConfigManager] Cannot build the theme script: {}", e);
+                return None;
+            }
+        };
+
+        let mut latest = self
+            .script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if latest.as_ref().map_or(true, |(kept, _)| *kept < generation) {
+            *latest = Some((generation, script.clone()));
+        }
+        Some(script)
+    }
+}
+
+fn eval_in<R: Runtime>(webview: &Webview<R>, script: &str) {
+    if let Err(e) = webview.eval(script) {
+        eprintln!(
+            "[ConfigManager] Cannot apply the theme to webview {}: {}",
+            webview.label(),
+            e
+        );
+    }
+}
+
+/// Applies the latest theme to a page that started loading or finished
+/// loading. Evaluated at both points, since depending on the platform the
+/// document may not be the new one yet when loading starts.
+pub(crate) fn on_page_load<R: Runtime>(webview: &Webview<R>) {
+    let app = webview.app_handle();
+    let Some(webviews) = app.try_state::<ThemedWebviews<R>>() else {
+        return;
+    };
+    if let Some(script) = webviews.current() {
+        eval_in(webview, &script);
+        return;
+    }
+
+    // Sin configuración al arrancar no hubo script: se construye ahora.
+    let Some(config_manager) = app.try_state::<crate::ConfigManager<R>>() else {
+        return;
+    };
+    let store = config_manager.inner().store().clone();
+    let app = app.clone();
+    let webview = webview.clone();
+    tauri::async_runtime::spawn(async move {
+        let webviews = app.state::<ThemedWebviews<R>>();
+        if let Some(script) = webviews.refresh(&store).await {
+            eval_in(&webview, &script);
+        }
+    });
+}
+
+/// Applies the current theme to every open webview.
+pub(crate) async fn reinject<R: Runtime>(app: &AppHandle<R>, store: &ConfigStore) {
+    let Some(webviews) = app.try_state::<ThemedWebviews<R>>() else {
+        return;
+    };
+    let Some(script) = webviews.refresh(store).await else {
+        return;
+    };
+    for webview in webviews.all() {
+        eval_in(&webview, &script);
+    }
+}
+
+/// Re-injects the theme whenever the scheme id, dark mode, corner radius or
+/// fonts change in the config. Changes to the active scheme file come from
+/// the scheme watcher, which calls [`reinject`].
+pub(crate) async fn follow_config<R: Runtime>(app: AppHandle<R>, store: ConfigStore) {
+    let mut changes = store.subscribe_changes();
+    let mut last = store
+        .load_config()
+        .await
+        .ok()
+        .map(|config| theme_inputs(&config));
+    loop {
+        // `store` mantiene vivo el canal, así que `recv` solo falla por
+        // `Lagged`; entonces se compara con el estado actual.
+        let config = match changes.recv().await {
+            Ok(config) => config,
+            Err(_) => match store.load_config().await {
+                Ok(config) => config,
+                Err(_) => continue,
+            },
+        };
+        let inputs = Some(theme_inputs(&config));
+        if inputs == last {
+            continue;
+        }
+        last = inputs;
+        reinject(&app, &store).await;
+    }
+}
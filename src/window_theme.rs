@@ -0,0 +1,131 @@
+//! Native theme and background of the app's own windows.
+//!
+//! Only the windows registered with [`crate::Builder::sync_window`] follow
+//! dark mode and the active scheme; the rest keep whatever the app set.
+
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Manager, Runtime, Theme};
+use vasak_config::{ConfigStore, VSKConfig};
+
+/// What the plugin keeps in sync on a native window, besides its theme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowSync {
+    /// Also paint the window background with `ui.background` of the active
+    /// scheme, in the variant matching dark mode.
+    pub background: bool,
+}
+
+/// Ventanas sincronizadas, por etiqueta; se guarda como estado del plugin.
+#[derive(Debug, Default)]
+pub(crate) struct SyncedWindows(pub HashMap<String, WindowSync>);
+
+/// Tema nativo y, si el esquema existe, color de fondo para el modo actual.
+async fn resolve(store: &ConfigStore) -> crate::Result<(Theme, Option<tauri::window::Color>)> {
+    let config = store.load_config().await?;
+    let theme = if config.style.darkmode {
+        Theme::Dark
+    } else {
+        Theme::Light
+    };
+    let background = store
+        .get_scheme_by_id(&config.style.color_scheme)
+        .await?
+        .map(|scheme| {
+            let variant = if config.style.darkmode {
+                &scheme.scheme.colors.dark
+            } else {
+                &scheme.scheme.colors.light
+            };
+            let [red, green, blue, alpha] = variant.ui.background.to_rgba8();
+            tauri::window::Color(red, green, blue, alpha)
+        });
+    Ok((theme, background))
+}
+
+fn apply_to_window<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    options: WindowSync,
+    theme: Theme,
+    background: Option<tauri::window::Color>,
+) {
+    // La ventana puede no existir todavía o ya estar cerrada.
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    if let Err(e) = window.set_theme(Some(theme)) {
+        eprintln!(
+            "[ConfigManager] Cannot set the theme of window {}: {}",
+            label, e
+        );
+    }
+    if let (true, Some(color)) = (options.background, background) {
+        if let Err(e) = window.set_background_color(Some(color)) {
+            eprintln!(
+                "[ConfigManager] Cannot set the background of window {}: {}",
+                label, e
+            );
+        }
+    }
+}
+
+/// Applies the current theme to the synced windows, or only to `label`.
+pub(crate) async fn apply<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &ConfigStore,
+    label: Option<&str>,
+) {
+    let Some(windows) = app.try_state::<SyncedWindows>() else {
+        return;
+    };
+    let windows: Vec<(String, WindowSync)> = windows
+        .0
+        .iter()
+        .filter(|(window, _)| label.map_or(true, |label| label == window.as_str()))
+        .map(|(window, options)| (window.clone(), *options))
+        .collect();
+    if windows.is_empty() {
+        return;
+    }
+
+    let (theme, background) = match resolve(store).await {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("[ConfigManager] Cannot resolve the window theme: {}", e);
+            return;
+        }
+    };
+    for (label, options) in windows {
+        apply_to_window(app, &label, options, theme, background);
+    }
+}
+
+/// Applies the theme whenever dark mode or the active scheme change in the
+/// config. Changes to the scheme file come from the scheme watcher, which
+/// calls [`apply`].
+pub(crate) async fn follow_config<R: Runtime>(app: AppHandle<R>, store: ConfigStore) {
+    let theme_of = |config: &VSKConfig| (config.style.darkmode, config.style.color_scheme.clone());
+    let mut changes = store.subscribe_changes();
+    let mut last = store
+        .load_config()
+        .await
+        .ok()
+        .map(|config| theme_of(&config));
+    loop {
+        // `store` mantiene vivo el canal, así que `recv` solo falla por `Lagged`.
+        let config = match changes.recv().await {
+            Ok(config) => config,
+            Err(_) => match store.load_config().await {
+                Ok(config) => config,
+                Err(_) => continue,
+            },
+        };
+        let theme = Some(theme_of(&config));
+        if theme == last {
+            continue;
+        }
+        last = theme;
+        apply(&app, &store, None).await;
+    }
+}